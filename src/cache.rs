@@ -0,0 +1,97 @@
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use lru::LruCache;
+
+use crate::client::RawResponse;
+use crate::clock::Clock;
+use crate::tools::daily::Domain;
+
+pub struct CacheConfig {
+    pub capacity: NonZeroUsize,
+    pub problem_ttl: Duration,
+    pub status_ttl: Duration,
+}
+
+struct Entry {
+    response: RawResponse,
+    expires_at: Instant,
+}
+
+/// In-memory LRU of successful backend responses, keyed by request path.
+pub struct ResponseCache {
+    config: CacheConfig,
+    entries: Mutex<LruCache<String, Entry>>,
+    clock: Arc<dyn Clock>,
+}
+
+impl ResponseCache {
+    pub fn new(config: CacheConfig, clock: Arc<dyn Clock>) -> Self {
+        let entries = Mutex::new(LruCache::new(config.capacity));
+        Self {
+            config,
+            entries,
+            clock,
+        }
+    }
+
+    pub fn get(&self, path: &str) -> Option<RawResponse> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(path) {
+            Some(entry) if entry.expires_at > Instant::now() => Some(entry.response.clone()),
+            Some(_) => {
+                entries.pop(path);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn put(&self, path: &str, response: &RawResponse) {
+        if response.status != 200 {
+            return;
+        }
+        let Some(ttl) = self.ttl_for(path) else {
+            return;
+        };
+        let entry = Entry {
+            response: response.clone(),
+            expires_at: Instant::now() + ttl,
+        };
+        self.entries.lock().unwrap().put(path.to_owned(), entry);
+    }
+
+    fn ttl_for(&self, path: &str) -> Option<Duration> {
+        let (route, query) = path.split_once('?').unwrap_or((path, ""));
+        if route == "/status" || route == "/api/v1/problems" {
+            Some(self.config.status_ttl)
+        } else if route == "/api/v1/daily" {
+            Some(until_rollover(query, self.clock.as_ref()))
+        } else if route.starts_with("/api/v1/problems/")
+            || route.starts_with("/api/v1/resolve/")
+            || route.starts_with("/api/v1/contests/")
+            || route.starts_with("/api/v1/similar")
+        {
+            Some(self.config.problem_ttl)
+        } else {
+            None
+        }
+    }
+}
+
+/// Time left until the next 00:00 in the timezone of the `domain` query parameter.
+fn until_rollover(query: &str, clock: &dyn Clock) -> Duration {
+    let domain = query
+        .split('&')
+        .find_map(|kv| kv.strip_prefix("domain="))
+        .and_then(|d| d.parse::<Domain>().ok())
+        .unwrap_or_default();
+    let now = clock.now().with_timezone(&domain.utc_offset());
+    let next_midnight = (now.date_naive() + chrono::Days::new(1))
+        .and_hms_opt(0, 0, 0)
+        .expect("midnight is a valid time");
+    (next_midnight - now.naive_local())
+        .to_std()
+        .unwrap_or(Duration::ZERO)
+}
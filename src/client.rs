@@ -1,29 +1,192 @@
-use std::time::Duration;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use reqwest::header::{AUTHORIZATION, CONTENT_TYPE, HeaderMap, HeaderValue};
+use reqwest::StatusCode;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::header::{AUTHORIZATION, CONTENT_TYPE, HeaderMap, HeaderValue, LOCATION, RETRY_AFTER};
 use rmcp::model::ErrorData;
+use serde::de::DeserializeOwned;
 
-use crate::error::protocol_error;
+use crate::cache::{CacheConfig, ResponseCache};
+use crate::clock::Clock;
+use crate::completion::Recent;
+use crate::error::{FetchError, format_api_error, protocol_error};
+use crate::store::{DiskStore, StoreConfig, is_persisted};
 
+#[derive(Clone)]
 pub struct RawResponse {
     pub status: u16,
     pub body: String,
     pub is_json: bool,
+    /// Set when the response was served from the on-disk store rather than the backend.
+    pub fetched_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl RawResponse {
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, ErrorData> {
+        if !self.is_json {
+            return Err(protocol_error("unexpected non-JSON response"));
+        }
+        serde_json::from_str(&self.body).map_err(|e| protocol_error(format!("invalid JSON: {e}")))
+    }
+}
+
+#[derive(Clone)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt; 0 disables retrying.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled on each subsequent one.
+    pub base_delay: Duration,
+    /// Upper bound on the time spent across all attempts and delays.
+    pub max_elapsed: Duration,
+}
+
+impl RetryPolicy {
+    /// Full-jitter exponential backoff: a random delay in `[0, base * 2^retry]`.
+    fn backoff(&self, retry: u32) -> Duration {
+        let ceiling = self
+            .base_delay
+            .saturating_mul(1u32.checked_shl(retry).unwrap_or(u32::MAX))
+            .min(self.max_elapsed);
+        ceiling.mul_f64(rand::random::<f64>())
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Parses `Retry-After` as either delay-seconds or an HTTP-date.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let delta = at.signed_duration_since(chrono::Utc::now());
+    Some(delta.to_std().unwrap_or(Duration::ZERO))
+}
+
+pub struct ClientOptions {
+    pub token: Option<String>,
+    pub retry: RetryPolicy,
+    pub cache: Option<CacheConfig>,
+    pub store: Option<StoreConfig>,
+    pub clock: Arc<dyn Clock>,
+}
+
+/// Image types returned to clients as image content.
+const IMAGE_TYPES: &[&str] = &["image/png", "image/jpeg", "image/gif", "image/webp"];
+const MAX_IMAGE_REDIRECTS: usize = 3;
+
+/// Whether `ip` is reachable on the public internet, i.e. not loopback, private,
+/// link-local (cloud metadata services live at 169.254.169.254), CGNAT or reserved.
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            !(v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_documentation()
+                || v4.is_multicast()
+                || a == 0
+                || a >= 240
+                || (a == 100 && (64..128).contains(&b)))
+        }
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => is_public(IpAddr::V4(v4)),
+            None => {
+                let first = v6.segments()[0];
+                !(v6.is_loopback()
+                    || v6.is_unspecified()
+                    || v6.is_multicast()
+                    || (first & 0xfe00) == 0xfc00
+                    || (first & 0xffc0) == 0xfe80)
+            }
+        },
+    }
+}
+
+/// DNS resolver for statement assets that drops non-public addresses, so a hostname
+/// cannot point image downloads at internal services.
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let host = name.as_str();
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, 0))
+                .await?
+                .filter(|addr| is_public(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{host} has no public address").into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Whether `url` may be fetched as a statement asset: http(s), a host equal to or below
+/// one of `hosts`, and no literal non-public IP.
+fn is_allowed_asset(url: &url::Url, hosts: &[String]) -> Result<(), String> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(format!("unsupported scheme '{}'", url.scheme()));
+    }
+    let public = match url.host() {
+        Some(url::Host::Ipv4(ip)) => is_public(IpAddr::V4(ip)),
+        Some(url::Host::Ipv6(ip)) => is_public(IpAddr::V6(ip)),
+        Some(url::Host::Domain(_)) => true,
+        None => false,
+    };
+    let host = url.host_str().unwrap_or_default().to_ascii_lowercase();
+    if !public {
+        return Err(format!("'{host}' is not a public address"));
+    }
+    let known = hosts.iter().any(|h| {
+        host == *h
+            || host
+                .strip_suffix(h.as_str())
+                .is_some_and(|rest| rest.ends_with('.'))
+    });
+    if known {
+        Ok(())
+    } else {
+        Err(format!("host '{host}' is not an allowed image host"))
+    }
 }
 
 #[derive(Clone)]
 pub struct OjClient {
     http: reqwest::Client,
+    /// Client without the API token, for images hosted by the platforms.
+    assets: reqwest::Client,
     base_url: String,
+    retry: RetryPolicy,
+    cache: Option<Arc<ResponseCache>>,
+    store: Option<Arc<DiskStore>>,
+    offline: bool,
+    recent: Arc<Recent>,
+    clock: Arc<dyn Clock>,
 }
 
 impl OjClient {
-    pub fn new(base_url: String, token: Option<String>) -> Result<Self, String> {
+    pub fn new(base_url: String, options: ClientOptions) -> Result<Self, String> {
         let mut builder = reqwest::ClientBuilder::new()
             .timeout(Duration::from_secs(30))
             .use_rustls_tls();
 
-        if let Some(t) = token {
+        if let Some(t) = options.token {
             let mut headers = HeaderMap::new();
             let mut val = HeaderValue::from_str(&format!("Bearer {t}"))
                 .map_err(|_| "token contains invalid header characters")?;
@@ -33,17 +196,212 @@ impl OjClient {
         }
 
         let http = builder.build().expect("failed to build HTTP client");
-        Ok(Self { http, base_url })
+        let assets = reqwest::ClientBuilder::new()
+            .timeout(Duration::from_secs(15))
+            .redirect(reqwest::redirect::Policy::none())
+            .dns_resolver(Arc::new(PublicResolver))
+            .use_rustls_tls()
+            .build()
+            .expect("failed to build HTTP client");
+        Ok(Self {
+            http,
+            assets,
+            base_url,
+            retry: options.retry,
+            cache: options
+                .cache
+                .map(|c| Arc::new(ResponseCache::new(c, options.clock.clone()))),
+            offline: options.store.as_ref().is_some_and(|s| s.offline),
+            store: options.store.map(|s| Arc::new(DiskStore::new(s.dir))),
+            recent: Arc::default(),
+            clock: options.clock,
+        })
     }
 
-    pub async fn get_raw(&self, path: &str) -> Result<RawResponse, ErrorData> {
-        let url = format!("{}{path}", self.base_url);
-        let resp = self
-            .http
-            .get(&url)
-            .send()
+    pub fn recent(&self) -> &Recent {
+        &self.recent
+    }
+
+    pub fn clock(&self) -> &dyn Clock {
+        self.clock.as_ref()
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    /// Responses kept in the on-disk store for paths starting with `prefix`; empty
+    /// without `--cache-dir`.
+    pub async fn stored(&self, prefix: &str) -> Vec<RawResponse> {
+        match &self.store {
+            Some(store) => store.load_prefix(prefix).await,
+            None => Vec::new(),
+        }
+    }
+
+    /// Downloads an image referenced by a problem statement, returning its MIME type and
+    /// bytes. Only PNG, JPEG, GIF and WebP are accepted, up to `max_bytes`, from `hosts`
+    /// (or their subdomains) on public addresses; each redirect is checked the same way.
+    pub async fn fetch_image(
+        &self,
+        url: &str,
+        hosts: &[String],
+        max_bytes: usize,
+    ) -> Result<(String, Vec<u8>), String> {
+        if self.offline {
+            return Err("images are not fetched in offline mode".into());
+        }
+        let mut url = url::Url::parse(url).map_err(|e| format!("invalid URL: {e}"))?;
+        let mut redirects = 0;
+        let mut resp = loop {
+            is_allowed_asset(&url, hosts)?;
+            let resp = self.assets.get(url.clone()).send().await.map_err(|e| {
+                // The resolver's refusal is only visible in the source chain.
+                let mut msg = format!("request failed: {e}");
+                let mut source = std::error::Error::source(&e);
+                while let Some(cause) = source {
+                    msg.push_str(&format!(": {cause}"));
+                    source = cause.source();
+                }
+                msg
+            })?;
+            if !resp.status().is_redirection() {
+                break resp;
+            }
+            redirects += 1;
+            if redirects > MAX_IMAGE_REDIRECTS {
+                return Err("too many redirects".into());
+            }
+            let location = resp
+                .headers()
+                .get(LOCATION)
+                .and_then(|v| v.to_str().ok())
+                .ok_or_else(|| format!("HTTP {} without a Location", resp.status()))?;
+            url = url
+                .join(location)
+                .map_err(|e| format!("invalid redirect: {e}"))?;
+        };
+        if !resp.status().is_success() {
+            return Err(format!("HTTP {}", resp.status()));
+        }
+        let mime = resp
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|ct| ct.split(';').next())
+            .map(|ct| ct.trim().to_ascii_lowercase())
+            .unwrap_or_default();
+        if !IMAGE_TYPES.contains(&mime.as_str()) {
+            return Err(format!("unsupported content type '{mime}'"));
+        }
+        let too_large = || format!("larger than {} KiB", max_bytes / 1024);
+        if resp.content_length().is_some_and(|n| n > max_bytes as u64) {
+            return Err(too_large());
+        }
+        let mut buf = Vec::new();
+        while let Some(chunk) = resp
+            .chunk()
             .await
-            .map_err(|e| protocol_error(format!("request failed: {e}")))?;
+            .map_err(|e| format!("read body failed: {e}"))?
+        {
+            if buf.len() + chunk.len() > max_bytes {
+                return Err(too_large());
+            }
+            buf.extend_from_slice(&chunk);
+        }
+        Ok((mime, buf))
+    }
+
+    /// Sends an idempotent GET, retrying transport failures and 429/502/503/504 responses
+    /// according to the configured [`RetryPolicy`].
+    async fn send_with_retry(&self, path: &str) -> Result<reqwest::Response, ErrorData> {
+        let url = format!("{}{path}", self.base_url);
+        let start = Instant::now();
+        let mut attempt = 0u32;
+
+        loop {
+            attempt += 1;
+            let outcome = self.http.get(&url).send().await;
+            let (reason, hinted) = match &outcome {
+                Ok(resp) if is_retryable_status(resp.status()) => {
+                    let hinted = match resp.status() {
+                        StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE => {
+                            retry_after(resp.headers())
+                        }
+                        _ => None,
+                    };
+                    (resp.status().to_string(), hinted)
+                }
+                Ok(resp) => {
+                    if attempt > 1 {
+                        tracing::info!("GET {path}: {} after {attempt} attempts", resp.status());
+                    }
+                    return outcome.map_err(|e| protocol_error(format!("request failed: {e}")));
+                }
+                Err(e) if e.is_builder() => {
+                    return Err(protocol_error(format!("request failed: {e}")));
+                }
+                Err(e) => (e.to_string(), None),
+            };
+
+            let delay = hinted.unwrap_or_else(|| self.retry.backoff(attempt - 1));
+            if attempt > self.retry.max_retries || start.elapsed() + delay > self.retry.max_elapsed
+            {
+                tracing::warn!("GET {path}: giving up after {attempt} attempts: {reason}");
+                return outcome.map_err(|e| protocol_error(format!("request failed: {e}")));
+            }
+            tracing::warn!(
+                "GET {path}: attempt {attempt} failed ({reason}), retrying in {}ms",
+                delay.as_millis()
+            );
+            drop(outcome);
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    pub async fn get_raw(&self, path: &str) -> Result<RawResponse, ErrorData> {
+        if self.offline {
+            return Ok(self.get_offline(path).await);
+        }
+        if let Some(hit) = self.cache.as_ref().and_then(|c| c.get(path)) {
+            tracing::debug!("GET {path}: cache hit");
+            return Ok(hit);
+        }
+        let resp = self.fetch_raw(path).await?;
+        if let Some(cache) = &self.cache {
+            cache.put(path, &resp);
+        }
+        if let Some(store) = &self.store {
+            store.save(path, &resp).await;
+        }
+        Ok(resp)
+    }
+
+    /// Serves a path from the on-disk store, answering misses with a synthetic 404 so
+    /// they surface as domain errors.
+    async fn get_offline(&self, path: &str) -> RawResponse {
+        if is_persisted(path)
+            && let Some(store) = &self.store
+            && let Some(resp) = store.load(path).await
+        {
+            return resp;
+        }
+        let detail = if is_persisted(path) {
+            format!("no cached copy of {path}; fetch it once while online with --cache-dir")
+        } else {
+            format!("{path} is not available in offline mode")
+        };
+        let body = serde_json::json!({ "status": 404, "title": "Offline", "detail": detail });
+        RawResponse {
+            status: 404,
+            body: body.to_string(),
+            is_json: true,
+            fetched_at: None,
+        }
+    }
+
+    async fn fetch_raw(&self, path: &str) -> Result<RawResponse, ErrorData> {
+        let resp = self.send_with_retry(path).await?;
 
         let status = resp.status().as_u16();
         let is_json = resp
@@ -79,6 +437,27 @@ impl OjClient {
             status,
             body,
             is_json,
+            fetched_at: None,
         })
     }
+
+    pub async fn get_json<T: DeserializeOwned>(&self, path: &str) -> Result<T, FetchError> {
+        Ok(self.get_json_dated(path).await?.0)
+    }
+
+    /// Like [`Self::get_json`], also returning when the data was fetched if it came
+    /// from the on-disk store.
+    pub async fn get_json_dated<T: DeserializeOwned>(
+        &self,
+        path: &str,
+    ) -> Result<(T, Option<chrono::DateTime<chrono::Utc>>), FetchError> {
+        let resp = self.get_raw(path).await?;
+        if resp.status != 200 {
+            return Err(FetchError::Domain(format_api_error(
+                resp.status,
+                &resp.body,
+            )));
+        }
+        Ok((resp.json()?, resp.fetched_at))
+    }
 }
@@ -0,0 +1,15 @@
+use chrono::{DateTime, Utc};
+
+/// Source of the current time, injectable so date handling does not depend on the
+/// machine clock.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
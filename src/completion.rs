@@ -0,0 +1,148 @@
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::sync::Mutex;
+
+use rmcp::model::{CompleteRequestParams, CompleteResult, CompletionInfo, Reference};
+
+use crate::client::OjClient;
+use crate::tools::status;
+
+const DEFAULT_SOURCES: &[&str] = &["leetcode", "codeforces", "atcoder", "luogu"];
+const DOMAINS: &[&str] = &["com", "cn"];
+const MAX_IDS_PER_SOURCE: usize = 200;
+
+#[derive(Default)]
+struct RecentInner {
+    sources: BTreeSet<String>,
+    ids: HashMap<String, VecDeque<String>>,
+    status_attempted: bool,
+}
+
+/// Platforms and problem IDs seen during this process, used for argument completion.
+#[derive(Default)]
+pub struct Recent {
+    inner: Mutex<RecentInner>,
+}
+
+impl Recent {
+    pub fn record_sources<'a>(&self, sources: impl IntoIterator<Item = &'a str>) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.sources.extend(sources.into_iter().map(str::to_owned));
+    }
+
+    pub fn record_problem(&self, source: &str, id: &str) {
+        if source.is_empty() || id.is_empty() {
+            return;
+        }
+        let mut inner = self.inner.lock().unwrap();
+        inner.sources.insert(source.to_owned());
+        let ids = inner.ids.entry(source.to_owned()).or_default();
+        ids.retain(|seen| seen != id);
+        ids.push_front(id.to_owned());
+        ids.truncate(MAX_IDS_PER_SOURCE);
+    }
+
+    fn sources(&self) -> Vec<String> {
+        let inner = self.inner.lock().unwrap();
+        let mut out: Vec<String> = DEFAULT_SOURCES.iter().map(|s| (*s).to_owned()).collect();
+        out.extend(
+            inner
+                .sources
+                .iter()
+                .filter(|s| !DEFAULT_SOURCES.contains(&s.as_str()))
+                .cloned(),
+        );
+        out
+    }
+
+    fn ids(&self, source: Option<&str>) -> Vec<String> {
+        let inner = self.inner.lock().unwrap();
+        match source {
+            Some(source) => inner
+                .ids
+                .get(source)
+                .map(|ids| ids.iter().cloned().collect())
+                .unwrap_or_default(),
+            None => inner.ids.values().flatten().cloned().collect(),
+        }
+    }
+
+    /// Returns true exactly once, so the platform list is probed from /status a single time.
+    fn take_status_probe(&self) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        !std::mem::replace(&mut inner.status_attempted, true)
+    }
+}
+
+enum Target {
+    Source,
+    Id,
+    Domain,
+}
+
+fn target(reference: &Reference, argument: &str) -> Option<Target> {
+    let accepts = match reference {
+        Reference::Prompt(p) => match p.name.as_str() {
+            "solve_problem" | "explain_approach" | "review_my_solution" => &["source", "id"][..],
+            "daily_practice" => &["domain"][..],
+            _ => &[][..],
+        },
+        Reference::Resource(r) => match r.uri.as_str() {
+            "oj://problem/{source}/{id}" => &["source", "id"][..],
+            "oj://daily/{domain}/{date}" => &["domain"][..],
+            _ => &[][..],
+        },
+    };
+    if !accepts.contains(&argument) {
+        return None;
+    }
+    match argument {
+        "source" => Some(Target::Source),
+        "id" => Some(Target::Id),
+        "domain" => Some(Target::Domain),
+        _ => None,
+    }
+}
+
+fn matching(candidates: impl IntoIterator<Item = String>, prefix: &str) -> CompletionInfo {
+    let prefix = prefix.trim().to_ascii_lowercase();
+    let mut values: Vec<String> = candidates
+        .into_iter()
+        .filter(|c| c.to_ascii_lowercase().starts_with(&prefix))
+        .collect();
+    let total = values.len();
+    values.truncate(CompletionInfo::MAX_VALUES);
+    CompletionInfo {
+        has_more: Some(total > values.len()),
+        total: Some(total as u32),
+        values,
+    }
+}
+
+pub async fn complete(client: &OjClient, request: &CompleteRequestParams) -> CompleteResult {
+    let recent = client.recent();
+    let prefix = request.argument.value.as_str();
+
+    let completion = match target(&request.r#ref, &request.argument.name) {
+        Some(Target::Source) => {
+            if recent.take_status_probe() {
+                // status::fetch records the platforms; /status may require a token, so
+                // failures just leave the built-in list.
+                let _ = status::fetch(client).await;
+            }
+            matching(recent.sources(), prefix)
+        }
+        Some(Target::Id) => {
+            let source = request
+                .context
+                .as_ref()
+                .and_then(|c| c.get_argument("source"))
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty());
+            matching(recent.ids(source), prefix)
+        }
+        Some(Target::Domain) => matching(DOMAINS.iter().map(|d| (*d).to_owned()), prefix),
+        None => CompletionInfo::default(),
+    };
+
+    CompleteResult { completion }
+}
@@ -1,4 +1,16 @@
-use crate::models::{Problem, SimilarResponse, StatusResponse};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+use ego_tree::iter::Edge;
+use rmcp::schemars;
+use scraper::{ElementRef, Html, Node, Selector};
+use serde::Deserialize;
+
+use crate::lang::canonical_language;
+use crate::models::{
+    CodeSnippet, Contest, IoMode, Problem, ProblemListResponse, Sample, SimilarResponse,
+    StatusResponse,
+};
 
 fn looks_like_html(s: &str) -> bool {
     let trimmed = s.trim();
@@ -14,20 +26,1413 @@ fn looks_like_html(s: &str) -> bool {
     TAGS.iter().any(|tag| lower.contains(tag))
 }
 
-pub fn html_to_markdown(content: &str) -> String {
+/// How formulas are written in converted statements.
+#[derive(Deserialize, schemars::JsonSchema, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MathStyle {
+    /// `$...$` inline and `$$...$$` display LaTeX.
+    #[default]
+    Latex,
+    /// Simple expressions as plain Unicode (`10^5` as `10⁵`, `\le` as `≤`), LaTeX otherwise.
+    Unicode,
+}
+
+/// Per-request choices for rendering a problem statement.
+#[derive(Default, Clone)]
+pub struct RenderOptions {
+    pub math: MathStyle,
+    /// Statement sections to include; all of them when empty.
+    pub sections: Vec<Section>,
+    /// Preferred statement language for multilingual statements.
+    pub lang: Option<StatementLang>,
+    /// Replace links by their text for more compact output.
+    pub strip_links: bool,
+}
+
+pub fn html_to_markdown(content: &str, opts: &RenderOptions) -> String {
     if content.trim().is_empty() {
         return "No description available.".into();
     }
     if !looks_like_html(content) {
-        return content.to_owned();
+        return normalize_markdown_math(content, opts.math);
     }
-    match std::panic::catch_unwind(|| htmd::convert(content)) {
+    let (protected, formulas) = protect_math(content);
+    let md = match std::panic::catch_unwind(|| htmd::convert(&protected)) {
         Ok(Ok(md)) if !md.trim().is_empty() => md,
-        _ => ammonia::clean_text(content),
+        _ => {
+            // `clean_text` escapes `<img>` along with everything else; keep the images as
+            // markdown so they are still listed and can be fetched.
+            let mut md = ammonia::clean_text(&protected);
+            for (alt, src) in html_images(&protected) {
+                md.push_str(&format!("\n\n![{alt}]({src})"));
+            }
+            md
+        }
+    };
+    restore_math(&md, &formulas, opts.math)
+}
+
+/// `(alt, src)` of every `<img>` with a source, ready to be written as markdown.
+fn html_images(content: &str) -> Vec<(String, String)> {
+    let doc = Html::parse_fragment(content);
+    doc.select(&selector("img[src]"))
+        .filter_map(|img| {
+            let src = img.value().attr("src")?.trim();
+            let alt = img.value().attr("alt").unwrap_or_default();
+            let alt: String = alt.chars().filter(|c| !matches!(c, '[' | ']')).collect();
+            let src = src
+                .replace(' ', "%20")
+                .replace('(', "%28")
+                .replace(')', "%29");
+            (!src.is_empty()).then_some((alt, src))
+        })
+        .collect()
+}
+
+struct Formula {
+    tex: String,
+    display: bool,
+}
+
+enum Segment<'a> {
+    Text(&'a str),
+    Math(Formula),
+}
+
+/// Math delimiters as `(open, close, display)`, longest first so Codeforces' `$$$` is
+/// not read as `$$` followed by `$`.
+const MATH_DELIMITERS: &[(&str, &str, bool)] = &[
+    ("$$$$$$", "$$$$$$", true),
+    ("$$$", "$$$", false),
+    ("$$", "$$", true),
+    ("\\[", "\\]", true),
+    ("\\(", "\\)", false),
+    ("$", "$", false),
+];
+
+/// End of the formula opened by a single `$` at `start`, following the usual markdown
+/// rule that keeps prices such as "$5 and $6" as text: no space just inside either
+/// delimiter, no digit right after the closing one, and no blank line in between.
+fn single_dollar_end(text: &str, start: usize) -> Option<usize> {
+    let body = &text[start..];
+    if body.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let mut from = 0;
+    while let Some(pos) = body[from..].find('$') {
+        let end = from + pos;
+        let inner = &body[..end];
+        from = end + 1;
+        if inner.is_empty() || inner.contains("\n\n") {
+            return None;
+        }
+        if inner.ends_with(char::is_whitespace) || inner.ends_with('\\') {
+            continue;
+        }
+        if body[end + 1..].starts_with(|c: char| c.is_ascii_digit()) {
+            continue;
+        }
+        return Some(start + end);
+    }
+    None
+}
+
+/// Splits text into plain runs and formulas. With `skip_code`, markdown code spans are
+/// left untouched and no formula extends into one.
+fn split_math(text: &str, skip_code: bool) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let (mut plain, mut i) = (0, 0);
+    while i < text.len() {
+        let rest = &text[i..];
+        if rest.starts_with("\\$") {
+            i += 2;
+            continue;
+        }
+        if skip_code && rest.starts_with('`') {
+            let ticks = rest.len() - rest.trim_start_matches('`').len();
+            let fence = &rest[..ticks];
+            i += match rest[ticks..].find(fence) {
+                Some(end) => 2 * ticks + end,
+                None => ticks,
+            };
+            continue;
+        }
+        let Some(&(open, close, display)) = MATH_DELIMITERS
+            .iter()
+            .find(|(open, _, _)| rest.starts_with(open))
+        else {
+            i += rest.chars().next().map_or(1, char::len_utf8);
+            continue;
+        };
+        let start = i + open.len();
+        let limit = match text[start..].find('`') {
+            Some(pos) if skip_code => start + pos,
+            _ => text.len(),
+        };
+        let end = if open == "$" {
+            single_dollar_end(&text[..limit], start)
+        } else {
+            text[start..limit].find(close).map(|pos| start + pos)
+        };
+        match end {
+            Some(end) if !text[start..end].trim().is_empty() => {
+                if plain < i {
+                    segments.push(Segment::Text(&text[plain..i]));
+                }
+                segments.push(Segment::Math(Formula {
+                    tex: text[start..end].trim().to_owned(),
+                    display,
+                }));
+                i = end + close.len();
+                plain = i;
+            }
+            _ => i = start,
+        }
+    }
+    if plain < text.len() {
+        segments.push(Segment::Text(&text[plain..]));
+    }
+    segments
+}
+
+/// Rewrites the formulas of a markdown statement (e.g. Luogu) in one consistent form,
+/// leaving fenced code blocks alone.
+fn normalize_markdown_math(content: &str, style: MathStyle) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut in_fence = false;
+    let mut block = String::new();
+    let flush = |block: &mut String, out: &mut String| {
+        for segment in split_math(block, true) {
+            match segment {
+                Segment::Text(t) => out.push_str(t),
+                Segment::Math(f) => out.push_str(&render_formula(&f, style)),
+            }
+        }
+        block.clear();
+    };
+    for line in content.split_inclusive('\n') {
+        if line.trim_start().starts_with("```") {
+            flush(&mut block, &mut out);
+            in_fence = !in_fence;
+            out.push_str(line);
+        } else if in_fence {
+            out.push_str(line);
+        } else {
+            block.push_str(line);
+        }
+    }
+    flush(&mut block, &mut out);
+    out
+}
+
+const PLACEHOLDER_OPEN: char = '\u{E000}';
+const PLACEHOLDER_CLOSE: char = '\u{E001}';
+
+fn placeholder(formulas: &mut Vec<Formula>, formula: Formula) -> String {
+    formulas.push(formula);
+    format!(
+        "{PLACEHOLDER_OPEN}{}{PLACEHOLDER_CLOSE}",
+        formulas.len() - 1
+    )
+}
+
+fn inside_code(node: ego_tree::NodeRef<Node>) -> bool {
+    node.ancestors().any(|a| {
+        a.value()
+            .as_element()
+            .is_some_and(|e| matches!(e.name(), "pre" | "code" | "script" | "style"))
+    })
+}
+
+/// Replaces every formula in an HTML statement by a placeholder that `htmd` passes
+/// through untouched, so TeX is neither escaped nor rendered twice. Understands KaTeX
+/// output (keeping its TeX annotation), MathJax v2 scripts, AtCoder's `<var>`, and
+/// `$`-style or `\(`-style delimiters in text.
+fn protect_math(content: &str) -> (String, Vec<Formula>) {
+    const MARKERS: &[&str] = &["$", "<var", "katex", "math/tex", "\\(", "\\["];
+    let mut formulas = Vec::new();
+    if !MARKERS.iter().any(|m| content.contains(m)) {
+        return (content.to_owned(), formulas);
+    }
+
+    let mut doc = Html::parse_fragment(content);
+    let annotation = selector(r#"annotation[encoding="application/x-tex"]"#);
+    let mut replacements = Vec::new();
+    let mut removals = Vec::new();
+
+    for el in doc.select(&selector(
+        ".katex-display, .katex, script[type^='math/tex'], var",
+    )) {
+        let inside_katex = el
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .any(|a| a.value().classes().any(|c| c.starts_with("katex")));
+        if inside_katex {
+            continue;
+        }
+        let name = el.value().name();
+        let classes: Vec<&str> = el.value().classes().collect();
+        let (tex, display) = if name == "var" {
+            let text: String = el.text().collect();
+            if inside_code(*el) {
+                // Inside a code block a formula can only be shown as its source.
+                replacements.push((el.id(), text));
+                continue;
+            }
+            (text, false)
+        } else if name == "script" {
+            let mode = el.value().attr("type").unwrap_or_default();
+            (el.text().collect(), mode.contains("mode=display"))
+        } else {
+            match el.select(&annotation).next() {
+                Some(a) => (a.text().collect(), classes.contains(&"katex-display")),
+                None => continue,
+            }
+        };
+        let text = placeholder(&mut formulas, Formula { tex, display });
+        replacements.push((el.id(), text));
+    }
+    // MathJax v2 renderings duplicate the script source kept above.
+    for el in doc.select(&selector(
+        ".MathJax_Preview, .MathJax, .MathJax_Display, .MJX_Assistive_MathML",
+    )) {
+        removals.push(el.id());
+    }
+
+    for (id, text) in replacements {
+        if let Some(mut node) = doc.tree.get_mut(id) {
+            node.insert_before(Node::Text(scraper::node::Text { text: text.into() }));
+            node.detach();
+        }
+    }
+    for id in removals {
+        if let Some(mut node) = doc.tree.get_mut(id) {
+            node.detach();
+        }
+    }
+
+    let texts: Vec<_> = doc
+        .tree
+        .nodes()
+        .filter(|n| n.value().is_text() && !inside_code(*n))
+        .map(|n| n.id())
+        .collect();
+    for id in texts {
+        let Some(mut node) = doc.tree.get_mut(id) else {
+            continue;
+        };
+        let Node::Text(text) = node.value() else {
+            continue;
+        };
+        let segments = split_math(text, false);
+        if !segments.iter().any(|s| matches!(s, Segment::Math(_))) {
+            continue;
+        }
+        let mut rewritten = String::new();
+        for segment in segments {
+            match segment {
+                Segment::Text(t) => rewritten.push_str(t),
+                Segment::Math(f) => rewritten.push_str(&placeholder(&mut formulas, f)),
+            }
+        }
+        text.text = rewritten.into();
+    }
+
+    (doc.root_element().inner_html(), formulas)
+}
+
+fn restore_math(md: &str, formulas: &[Formula], style: MathStyle) -> String {
+    let mut out = String::with_capacity(md.len());
+    let mut rest = md;
+    while let Some(start) = rest.find(PLACEHOLDER_OPEN) {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + PLACEHOLDER_OPEN.len_utf8()..];
+        let formula = after.find(PLACEHOLDER_CLOSE).and_then(|end| {
+            let index: usize = after[..end].parse().ok()?;
+            Some((formulas.get(index)?, end))
+        });
+        match formula {
+            Some((f, end)) => {
+                out.push_str(&render_formula(f, style));
+                rest = &after[end + PLACEHOLDER_CLOSE.len_utf8()..];
+            }
+            None => rest = after,
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn render_formula(f: &Formula, style: MathStyle) -> String {
+    if style == MathStyle::Unicode
+        && let Some(text) = unicode_math(&f.tex)
+    {
+        return text;
+    }
+    if f.display {
+        format!("$${}$$", f.tex)
+    } else {
+        format!("${}$", f.tex)
+    }
+}
+
+/// Commands rendered as a spaced operator or relation.
+const TEX_OPERATORS: &[(&str, &str)] = &[
+    ("le", "≤"),
+    ("leq", "≤"),
+    ("leqslant", "≤"),
+    ("ge", "≥"),
+    ("geq", "≥"),
+    ("geqslant", "≥"),
+    ("lt", "<"),
+    ("gt", ">"),
+    ("ne", "≠"),
+    ("neq", "≠"),
+    ("approx", "≈"),
+    ("equiv", "≡"),
+    ("sim", "∼"),
+    ("ll", "≪"),
+    ("gg", "≫"),
+    ("times", "×"),
+    ("div", "÷"),
+    ("pm", "±"),
+    ("mp", "∓"),
+    ("cdot", "⋅"),
+    ("ast", "∗"),
+    ("circ", "∘"),
+    ("to", "→"),
+    ("rightarrow", "→"),
+    ("leftarrow", "←"),
+    ("leftrightarrow", "↔"),
+    ("Rightarrow", "⇒"),
+    ("Leftarrow", "⇐"),
+    ("implies", "⇒"),
+    ("iff", "⇔"),
+    ("in", "∈"),
+    ("notin", "∉"),
+    ("subset", "⊂"),
+    ("subseteq", "⊆"),
+    ("supset", "⊃"),
+    ("supseteq", "⊇"),
+    ("cup", "∪"),
+    ("cap", "∩"),
+    ("setminus", "∖"),
+    ("oplus", "⊕"),
+    ("otimes", "⊗"),
+    ("land", "∧"),
+    ("wedge", "∧"),
+    ("lor", "∨"),
+    ("vee", "∨"),
+    ("mid", "∣"),
+    ("bmod", "mod"),
+    ("mod", "mod"),
+];
+
+/// Commands rendered as a plain symbol or word.
+const TEX_SYMBOLS: &[(&str, &str)] = &[
+    ("ldots", "…"),
+    ("dots", "…"),
+    ("cdots", "⋯"),
+    ("vdots", "⋮"),
+    ("infty", "∞"),
+    ("neg", "¬"),
+    ("lnot", "¬"),
+    ("forall", "∀"),
+    ("exists", "∃"),
+    ("emptyset", "∅"),
+    ("varnothing", "∅"),
+    ("partial", "∂"),
+    ("sum", "∑"),
+    ("prod", "∏"),
+    ("lfloor", "⌊"),
+    ("rfloor", "⌋"),
+    ("lceil", "⌈"),
+    ("rceil", "⌉"),
+    ("langle", "⟨"),
+    ("rangle", "⟩"),
+    ("vert", "|"),
+    ("lvert", "|"),
+    ("rvert", "|"),
+    ("prime", "′"),
+    ("quad", " "),
+    ("qquad", " "),
+    ("max", "max"),
+    ("min", "min"),
+    ("gcd", "gcd"),
+    ("log", "log"),
+    ("ln", "ln"),
+    ("lim", "lim"),
+    ("sin", "sin"),
+    ("cos", "cos"),
+    ("alpha", "α"),
+    ("beta", "β"),
+    ("gamma", "γ"),
+    ("delta", "δ"),
+    ("epsilon", "ϵ"),
+    ("varepsilon", "ε"),
+    ("zeta", "ζ"),
+    ("eta", "η"),
+    ("theta", "θ"),
+    ("kappa", "κ"),
+    ("lambda", "λ"),
+    ("mu", "μ"),
+    ("nu", "ν"),
+    ("xi", "ξ"),
+    ("pi", "π"),
+    ("rho", "ρ"),
+    ("sigma", "σ"),
+    ("tau", "τ"),
+    ("phi", "ϕ"),
+    ("varphi", "φ"),
+    ("chi", "χ"),
+    ("psi", "ψ"),
+    ("omega", "ω"),
+    ("Gamma", "Γ"),
+    ("Delta", "Δ"),
+    ("Theta", "Θ"),
+    ("Lambda", "Λ"),
+    ("Pi", "Π"),
+    ("Sigma", "Σ"),
+    ("Phi", "Φ"),
+    ("Omega", "Ω"),
+];
+
+/// Commands that only affect TeX layout.
+const TEX_IGNORED: &[&str] = &[
+    "left",
+    "right",
+    "displaystyle",
+    "textstyle",
+    "limits",
+    "nolimits",
+    "big",
+    "Big",
+    "bigl",
+    "bigr",
+    "Bigl",
+    "Bigr",
+];
+
+/// Commands whose braced argument is shown as-is.
+const TEX_TEXT: &[&str] = &[
+    "text",
+    "textrm",
+    "textbf",
+    "texttt",
+    "mathrm",
+    "mathit",
+    "mathbf",
+    "mathtt",
+    "operatorname",
+];
+
+const SUPERSCRIPTS: &[(char, char)] = &[
+    ('0', '⁰'),
+    ('1', '¹'),
+    ('2', '²'),
+    ('3', '³'),
+    ('4', '⁴'),
+    ('5', '⁵'),
+    ('6', '⁶'),
+    ('7', '⁷'),
+    ('8', '⁸'),
+    ('9', '⁹'),
+    ('+', '⁺'),
+    ('-', '⁻'),
+    ('=', '⁼'),
+    ('(', '⁽'),
+    (')', '⁾'),
+    ('a', 'ᵃ'),
+    ('b', 'ᵇ'),
+    ('c', 'ᶜ'),
+    ('d', 'ᵈ'),
+    ('e', 'ᵉ'),
+    ('i', 'ⁱ'),
+    ('j', 'ʲ'),
+    ('k', 'ᵏ'),
+    ('m', 'ᵐ'),
+    ('n', 'ⁿ'),
+    ('p', 'ᵖ'),
+    ('t', 'ᵗ'),
+    ('x', 'ˣ'),
+    ('y', 'ʸ'),
+    ('T', 'ᵀ'),
+    ('′', '′'),
+];
+
+const SUBSCRIPTS: &[(char, char)] = &[
+    ('0', '₀'),
+    ('1', '₁'),
+    ('2', '₂'),
+    ('3', '₃'),
+    ('4', '₄'),
+    ('5', '₅'),
+    ('6', '₆'),
+    ('7', '₇'),
+    ('8', '₈'),
+    ('9', '₉'),
+    ('+', '₊'),
+    ('-', '₋'),
+    ('=', '₌'),
+    ('(', '₍'),
+    (')', '₎'),
+    ('a', 'ₐ'),
+    ('e', 'ₑ'),
+    ('h', 'ₕ'),
+    ('i', 'ᵢ'),
+    ('j', 'ⱼ'),
+    ('k', 'ₖ'),
+    ('l', 'ₗ'),
+    ('m', 'ₘ'),
+    ('n', 'ₙ'),
+    ('o', 'ₒ'),
+    ('p', 'ₚ'),
+    ('r', 'ᵣ'),
+    ('s', 'ₛ'),
+    ('t', 'ₜ'),
+    ('u', 'ᵤ'),
+    ('v', 'ᵥ'),
+    ('x', 'ₓ'),
+];
+
+fn lookup<'a>(table: &[(&str, &'a str)], name: &str) -> Option<&'a str> {
+    table.iter().find(|(k, _)| *k == name).map(|(_, v)| *v)
+}
+
+type TexChars<'a> = std::iter::Peekable<std::str::Chars<'a>>;
+
+/// Contents of a braced group whose `{` is next, or the next single token.
+fn tex_argument(chars: &mut TexChars) -> Option<String> {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+    match chars.next()? {
+        '{' => {
+            let (mut depth, mut group) = (1, String::new());
+            for c in chars.by_ref() {
+                match c {
+                    '{' => depth += 1,
+                    '}' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return Some(group);
+                        }
+                    }
+                    _ => {}
+                }
+                group.push(c);
+            }
+            None
+        }
+        '\\' => {
+            let mut command = String::from('\\');
+            while let Some(c) = chars.next_if(|c| c.is_ascii_alphabetic()) {
+                command.push(c);
+            }
+            Some(command)
+        }
+        c => Some(c.to_string()),
+    }
+}
+
+fn script(text: &str, table: &[(char, char)]) -> Option<String> {
+    text.chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| table.iter().find(|(k, _)| *k == c).map(|(_, v)| *v))
+        .collect()
+}
+
+/// Renders simple TeX as plain Unicode, or `None` when the expression uses anything
+/// without a faithful text form (fractions of sums, matrices, unknown commands, ...).
+fn unicode_math(tex: &str) -> Option<String> {
+    let mut out = String::new();
+    let mut chars = tex.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                let mut name = String::new();
+                while let Some(c) = chars.next_if(|c| c.is_ascii_alphabetic()) {
+                    name.push(c);
+                }
+                if name.is_empty() {
+                    match chars.next()? {
+                        c @ ('{' | '}' | '$' | '%' | '#' | '_' | '&') => out.push(c),
+                        ',' | ':' | ';' | '>' | ' ' => out.push(' '),
+                        '!' => {}
+                        _ => return None,
+                    }
+                } else if let Some(op) = lookup(TEX_OPERATORS, &name) {
+                    out.push_str(&format!(" {op} "));
+                } else if let Some(symbol) = lookup(TEX_SYMBOLS, &name) {
+                    out.push_str(symbol);
+                } else if TEX_TEXT.contains(&name.as_str()) {
+                    out.push_str(&tex_argument(&mut chars)?);
+                } else if name == "sqrt" {
+                    let inner = unicode_math(&tex_argument(&mut chars)?)?;
+                    if inner.chars().count() == 1 {
+                        out.push_str(&format!("√{inner}"));
+                    } else {
+                        out.push_str(&format!("√({inner})"));
+                    }
+                } else if matches!(name.as_str(), "frac" | "dfrac" | "tfrac") {
+                    let wrap = |s: String| {
+                        if s.chars().all(char::is_alphanumeric) {
+                            s
+                        } else {
+                            format!("({s})")
+                        }
+                    };
+                    let num = unicode_math(&tex_argument(&mut chars)?)?;
+                    let den = unicode_math(&tex_argument(&mut chars)?)?;
+                    out.push_str(&format!("{}/{}", wrap(num), wrap(den)));
+                } else if !TEX_IGNORED.contains(&name.as_str()) {
+                    return None;
+                }
+            }
+            '^' | '_' => {
+                let inner = unicode_math(&tex_argument(&mut chars)?)?;
+                let table = if c == '^' { SUPERSCRIPTS } else { SUBSCRIPTS };
+                out.push_str(&script(&inner, table)?);
+            }
+            '{' | '}' => {}
+            '~' => out.push(' '),
+            '&' => return None,
+            c => out.push(c),
+        }
+    }
+    Some(out.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+fn format_time_limit(ms: u64) -> String {
+    if ms < 1000 {
+        format!("{ms} ms")
+    } else if ms.is_multiple_of(1000) {
+        format!("{} s", ms / 1000)
+    } else {
+        format!("{:.1} s", ms as f64 / 1000.0)
+    }
+}
+
+/// Header lines for time/memory limits, I/O mode and special judge, empty when the
+/// backend provides none of them.
+fn format_limits(p: &Problem) -> String {
+    let mut parts = Vec::new();
+    if let Some(ms) = p.time_limit_ms {
+        parts.push(format!("Time: {}", format_time_limit(ms)));
+    }
+    if let Some(mb) = p.memory_limit_mb {
+        parts.push(format!("Memory: {mb} MB"));
+    }
+    match p.io_mode {
+        Some(IoMode::Stdin) => parts.push("I/O: standard input/output".into()),
+        Some(IoMode::File) => parts.push("I/O: file".into()),
+        Some(IoMode::Interactive) => parts.push("I/O: interactive".into()),
+        None => {}
+    }
+    if p.special_judge == Some(true) {
+        parts.push("Special judge".into());
+    }
+
+    let mut out = String::new();
+    if !parts.is_empty() {
+        out.push_str(&format!("- Limits: {}\n", parts.join(" | ")));
+    }
+    if p.io_mode == Some(IoMode::Interactive) {
+        out.push_str(
+            "- **Interactive problem**: the program talks to a judge program; samples show one possible dialogue\n",
+        );
+    }
+    out
+}
+
+/// A part of a problem statement that can be requested on its own.
+#[derive(Deserialize, schemars::JsonSchema, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Section {
+    Description,
+    Input,
+    Output,
+    Constraints,
+    Examples,
+    Notes,
+    Hints,
+}
+
+impl Section {
+    fn title(self) -> &'static str {
+        match self {
+            Self::Description => "Description",
+            Self::Input => "Input",
+            Self::Output => "Output",
+            Self::Constraints => "Constraints",
+            Self::Examples => "Examples",
+            Self::Notes => "Notes",
+            Self::Hints => "Hints",
+        }
+    }
+}
+
+/// Statement chunks in order, each in the content's own format (HTML or markdown).
+type SectionParts = Vec<(Section, String)>;
+
+/// Section named by a statement heading in English, Japanese or Chinese. Sample
+/// headings are checked first since they also mention input and output.
+fn section_for_heading(heading: &str) -> Option<Section> {
+    const KEYWORDS: &[(Section, &[&str])] = &[
+        (
+            Section::Examples,
+            &["sample", "example", "入力例", "出力例", "样例", "示例"],
+        ),
+        (
+            Section::Constraints,
+            &["constraint", "制約", "数据范围", "约束"],
+        ),
+        (Section::Input, &["input", "入力", "输入"]),
+        (Section::Output, &["output", "出力", "输出"]),
+        (
+            Section::Description,
+            &[
+                "statement",
+                "description",
+                "background",
+                "legend",
+                "問題文",
+                "题目描述",
+                "题目背景",
+            ],
+        ),
+        (Section::Notes, &["note", "hint", "注記", "提示", "说明"]),
+    ];
+    let lower = heading.to_lowercase();
+    KEYWORDS
+        .iter()
+        .find(|(_, words)| words.iter().any(|w| lower.contains(w)))
+        .map(|(section, _)| *section)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn node_html(node: ego_tree::NodeRef<Node>) -> String {
+    match node.value() {
+        Node::Element(_) => ElementRef::wrap(node)
+            .map(|el| el.html())
+            .unwrap_or_default(),
+        Node::Text(text) => escape_html(text),
+        _ => String::new(),
+    }
+}
+
+/// LeetCode statements are a flat run of paragraphs: the description, then "Example N:"
+/// blocks, a "Constraints:" list and an optional "Follow-up" (or 示例/提示/进阶 on
+/// leetcode.cn).
+fn leetcode_sections(doc: &Html) -> SectionParts {
+    let mut container = doc.root_element();
+    // Some statements wrap everything in a single <div>.
+    loop {
+        let mut elements = container.children().filter_map(ElementRef::wrap);
+        let has_text = container
+            .children()
+            .any(|n| n.value().as_text().is_some_and(|t| !t.trim().is_empty()));
+        match (elements.next(), elements.next()) {
+            (Some(only), None) if !has_text && only.value().name() == "div" => container = only,
+            _ => break,
+        }
+    }
+
+    let mut current = Section::Description;
+    let mut parts = Vec::new();
+    for child in container.children() {
+        let text = match ElementRef::wrap(child) {
+            Some(el) => el.text().collect::<String>(),
+            None => child
+                .value()
+                .as_text()
+                .map(|t| t.to_string())
+                .unwrap_or_default(),
+        };
+        let label = text.trim();
+        if label.starts_with("Example") || label.starts_with("示例") {
+            current = Section::Examples;
+        } else if matches!(label.trim_end_matches([':', '：']), "Constraints" | "提示") {
+            current = Section::Constraints;
+            continue;
+        } else if label.starts_with("Follow") || label.starts_with("进阶") {
+            current = Section::Notes;
+        }
+        parts.push((current, node_html(child)));
+    }
+    parts
+}
+
+fn codeforces_sections(doc: &Html) -> SectionParts {
+    let Some(statement) = doc.select(&selector("div.problem-statement")).next() else {
+        return Vec::new();
+    };
+    let mut parts = Vec::new();
+    for child in statement.children().filter_map(ElementRef::wrap) {
+        let has = |class: &str| child.value().classes().any(|c| c == class);
+        let section = if has("header") {
+            continue;
+        } else if has("input-specification") {
+            Section::Input
+        } else if has("output-specification") {
+            Section::Output
+        } else if has("sample-tests") {
+            Section::Examples
+        } else if has("note") {
+            Section::Notes
+        } else {
+            Section::Description
+        };
+        let html = child
+            .children()
+            .filter(|n| {
+                ElementRef::wrap(*n)
+                    .is_none_or(|el| !el.value().classes().any(|c| c == "section-title"))
+            })
+            .map(node_html)
+            .collect();
+        parts.push((section, html));
+    }
+    parts
+}
+
+/// AtCoder statements are `<section>`s headed by an `<h3>`; sections with other
+/// headings (e.g. scoring) go to the notes.
+fn atcoder_sections(doc: &Html) -> SectionParts {
+    let section_sel = selector("section");
+    let h3 = selector("h3");
+    let mut parts = Vec::new();
+    for section in doc.select(&section_sel) {
+        let nested = section
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .any(|a| a.value().name() == "section");
+        let Some(heading) = section.select(&h3).next().filter(|_| !nested) else {
+            continue;
+        };
+        let title: String = heading.text().collect();
+        let html = match section_for_heading(&title) {
+            Some(kind) => {
+                let body = section
+                    .children()
+                    .filter(|n| n.id() != heading.id())
+                    .map(node_html)
+                    .collect();
+                (kind, body)
+            }
+            None => (Section::Notes, section.inner_html()),
+        };
+        parts.push(html);
+    }
+    parts
+}
+
+/// Markdown statements (e.g. Luogu) split at headings; a heading that names no section
+/// stays with the one before it, and a leading or title heading is dropped.
+fn markdown_sections(content: &str, title: &str) -> SectionParts {
+    let mut parts: SectionParts = Vec::new();
+    let mut current = Section::Description;
+    let mut in_fence = false;
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") {
+            in_fence = !in_fence;
+        } else if !in_fence && trimmed.starts_with('#') {
+            let heading = trimmed.trim_start_matches('#').trim();
+            let leading_title = parts.is_empty() && !trimmed.starts_with("##");
+            if heading == title.trim() || leading_title {
+                continue;
+            }
+            // Sub-headings of the examples ("Sample Input 1") are kept.
+            if let Some(section) = section_for_heading(heading)
+                && (section != Section::Examples || current != Section::Examples)
+            {
+                current = section;
+                continue;
+            }
+        }
+        match parts.last_mut() {
+            Some((section, text)) if *section == current => text.push_str(line),
+            _ => parts.push((current, line.to_owned())),
+        }
+    }
+    parts
+}
+
+/// Splits a statement into sections with the parser for its platform, trying the others
+/// when that finds nothing beyond a description.
+fn split_sections(p: &Problem, content: &str) -> SectionParts {
+    let recognized =
+        |parts: &[(Section, String)]| parts.iter().any(|(s, _)| *s != Section::Description);
+    if !looks_like_html(content) {
+        let parts = markdown_sections(content, &p.title);
+        return if recognized(&parts) {
+            parts
+        } else {
+            Vec::new()
+        };
+    }
+    let doc = Html::parse_fragment(content);
+    let parsers: [fn(&Html) -> SectionParts; 3] = match p.source.to_ascii_lowercase().as_str() {
+        "codeforces" => [codeforces_sections, atcoder_sections, leetcode_sections],
+        "atcoder" => [atcoder_sections, codeforces_sections, leetcode_sections],
+        _ => [leetcode_sections, codeforces_sections, atcoder_sections],
+    };
+    parsers
+        .iter()
+        .map(|parse| parse(&doc))
+        .find(|parts| recognized(parts))
+        .unwrap_or_default()
+}
+
+fn format_hints(hints: &[String], opts: &RenderOptions) -> String {
+    hints
+        .iter()
+        .enumerate()
+        .map(|(i, hint)| format!("{}. {}\n", i + 1, html_to_markdown(hint, opts).trim()))
+        .collect()
+}
+
+/// Only the requested sections, in the order of [`Section`]. Examples are shown as
+/// extracted samples when the statement has any.
+fn format_sections(p: &Problem, content: &str, opts: &RenderOptions) -> String {
+    let mut wanted = opts.sections.clone();
+    wanted.sort_unstable();
+    wanted.dedup();
+
+    let parts = split_sections(p, content);
+    // Samples come from the statement being shown, so a translation keeps its own
+    // explanations.
+    let samples = if wanted.contains(&Section::Examples) {
+        samples_in(&p.source, content)
+    } else {
+        Vec::new()
+    };
+    let mut out = String::new();
+    if parts.is_empty() && wanted.iter().any(|s| *s != Section::Hints) {
+        out.push_str(
+            "_Sections could not be identified in this statement; showing it in full._\n\n",
+        );
+        out.push_str(html_to_markdown(content, opts).trim_end());
+        out.push('\n');
+        wanted.retain(|s| *s == Section::Hints);
+    }
+
+    for section in wanted {
+        let body = match section {
+            Section::Hints => format_hints(p.hints.as_deref().unwrap_or_default(), opts),
+            Section::Examples if !samples.is_empty() => samples
+                .iter()
+                .enumerate()
+                .map(|(i, s)| format!("#{}", format_sample(i + 1, s)))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => {
+                let raw: String = parts
+                    .iter()
+                    .filter(|(s, _)| *s == section)
+                    .map(|(_, chunk)| chunk.as_str())
+                    .collect();
+                if raw.trim().is_empty() {
+                    String::new()
+                } else {
+                    html_to_markdown(&raw, opts)
+                }
+            }
+        };
+        let body = body.trim();
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        if body.is_empty() {
+            out.push_str(&format!(
+                "## {}\n\n_Not present in this statement._\n",
+                section.title()
+            ));
+        } else {
+            out.push_str(&format!("## {}\n\n{body}\n", section.title()));
+        }
+    }
+    out
+}
+
+/// Language of a problem statement.
+#[derive(Deserialize, schemars::JsonSchema, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StatementLang {
+    #[default]
+    En,
+    Ja,
+    Zh,
+}
+
+impl StatementLang {
+    fn name(self) -> &'static str {
+        match self {
+            Self::En => "English",
+            Self::Ja => "Japanese",
+            Self::Zh => "Chinese",
+        }
+    }
+}
+
+/// Title and statement in one language, with the header line reporting the choice.
+struct Localized<'a> {
+    title: &'a str,
+    content: Cow<'a, str>,
+    header: String,
+}
+
+/// Picks the requested language (English by default) from AtCoder's `span.lang-en` /
+/// `span.lang-ja` pair or a LeetCode CN translation, falling back to whatever version
+/// exists. Statements with a single language are returned as they are.
+fn localize<'a>(p: &'a Problem, requested: Option<StatementLang>) -> Localized<'a> {
+    let content = p.content.as_deref().unwrap_or("");
+    let mut versions: Vec<(StatementLang, &str, Cow<str>)> = Vec::new();
+    if content.contains("lang-") && looks_like_html(content) {
+        let doc = Html::parse_fragment(content);
+        for (lang, sel) in [
+            (StatementLang::En, "span.lang-en"),
+            (StatementLang::Ja, "span.lang-ja"),
+        ] {
+            if let Some(el) = doc.select(&selector(sel)).next() {
+                versions.push((lang, &p.title, Cow::Owned(el.inner_html())));
+            }
+        }
+    }
+    if let Some(zh) = p
+        .translated_content
+        .as_deref()
+        .filter(|c| !c.trim().is_empty())
+    {
+        let title = p.translated_title.as_deref().unwrap_or(&p.title);
+        versions.push((StatementLang::En, &p.title, Cow::Borrowed(content)));
+        versions.push((StatementLang::Zh, title, Cow::Borrowed(zh)));
+    }
+
+    if versions.is_empty() {
+        let header = requested
+            .map(|l| format!("- Language: original ({} not available)\n", l.name()))
+            .unwrap_or_default();
+        return Localized {
+            title: &p.title,
+            content: Cow::Borrowed(content),
+            header,
+        };
+    }
+    let wanted = requested.unwrap_or_default();
+    let index = versions.iter().position(|v| v.0 == wanted).unwrap_or(0);
+    let (lang, title, content) = versions.swap_remove(index);
+    let header = if lang == wanted {
+        format!("- Language: {}\n", lang.name())
+    } else {
+        format!(
+            "- Language: {} ({} not available)\n",
+            lang.name(),
+            wanted.name()
+        )
+    };
+    Localized {
+        title,
+        content,
+        header,
+    }
+}
+
+/// A markdown link `[text](target)` or image `![alt](target)` found in converted text.
+struct MdLink {
+    /// Byte range of the whole link, from `!`/`[` through `)`.
+    span: std::ops::Range<usize>,
+    /// Byte range of the link text between the brackets.
+    text: std::ops::Range<usize>,
+    /// Byte range of the destination, without any title.
+    target: std::ops::Range<usize>,
+    image: bool,
+}
+
+/// Byte ranges of fenced code blocks.
+fn fenced_ranges(md: &str) -> Vec<std::ops::Range<usize>> {
+    let mut ranges = Vec::new();
+    let (mut offset, mut open) = (0, None);
+    for line in md.split_inclusive('\n') {
+        if line.trim_start().starts_with("```") {
+            match open.take() {
+                Some(start) => ranges.push(start..offset + line.len()),
+                None => open = Some(offset),
+            }
+        }
+        offset += line.len();
+    }
+    if let Some(start) = open {
+        ranges.push(start..md.len());
+    }
+    ranges
+}
+
+/// Byte ranges of code (fenced blocks and inline spans) and formulas, where `](` is
+/// not a link.
+fn verbatim_ranges(md: &str) -> Vec<std::ops::Range<usize>> {
+    let fences = fenced_ranges(md);
+    let mut ranges = Vec::new();
+    let mut i = 0;
+    while i < md.len() {
+        if let Some(fence) = fences.iter().find(|r| r.start == i) {
+            ranges.push(fence.clone());
+            i = fence.end;
+            continue;
+        }
+        let next_fence = fences
+            .iter()
+            .map(|r| r.start)
+            .find(|&start| start > i)
+            .unwrap_or(md.len());
+        let text = &md[..next_fence];
+        let rest = &text[i..];
+        if rest.starts_with("\\$") || rest.starts_with("\\`") {
+            i += 2;
+            continue;
+        }
+        if rest.starts_with('`') {
+            let ticks = rest.len() - rest.trim_start_matches('`').len();
+            let fence = &rest[..ticks];
+            match rest[ticks..].find(fence) {
+                Some(end) => {
+                    ranges.push(i..i + 2 * ticks + end);
+                    i += 2 * ticks + end;
+                }
+                None => i += ticks,
+            }
+            continue;
+        }
+        let Some(&(open, close, _)) = MATH_DELIMITERS
+            .iter()
+            .find(|(open, _, _)| rest.starts_with(open))
+        else {
+            i += rest.chars().next().map_or(1, char::len_utf8);
+            continue;
+        };
+        let start = i + open.len();
+        let end = if open == "$" {
+            single_dollar_end(text, start)
+        } else {
+            text[start..].find(close).map(|pos| start + pos)
+        };
+        match end {
+            Some(end) => {
+                ranges.push(i..end + close.len());
+                i = end + close.len();
+            }
+            None => i = start,
+        }
+    }
+    ranges
+}
+
+/// Start of the `[` matching the `]` at `close`, skipping escaped brackets.
+fn link_text_start(md: &str, close: usize) -> Option<usize> {
+    let bytes = md.as_bytes();
+    let mut depth = 0;
+    for i in (0..close).rev() {
+        if i > 0 && bytes[i - 1] == b'\\' {
+            continue;
+        }
+        match bytes[i] {
+            b']' => depth += 1,
+            b'[' if depth == 0 => return Some(i),
+            b'[' => depth -= 1,
+            b'\n' if i > 0 && bytes[i - 1] == b'\n' => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// End of a link destination starting at `start` and the position of the closing `)`,
+/// allowing balanced and escaped parentheses and an optional quoted title.
+fn link_target_end(md: &str, start: usize) -> Option<(usize, usize)> {
+    let bytes = md.as_bytes();
+    let mut i = start;
+    if bytes.get(i) == Some(&b'<') {
+        i += md[i..].find('>')? + 1;
+    } else {
+        let mut depth = 0;
+        while let Some(&b) = bytes.get(i) {
+            match b {
+                // Only ASCII punctuation can be escaped, so the skip never runs past
+                // the end or into a multi-byte character.
+                b'\\' if bytes.get(i + 1).is_some_and(u8::is_ascii_punctuation) => i += 1,
+                b'(' => depth += 1,
+                b')' if depth == 0 => break,
+                b')' => depth -= 1,
+                b' ' | b'\n' => break,
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+    let end = i;
+    let close = end + md[end..].find(')')?;
+    let title = md[end..close].trim();
+    let quoted =
+        title.is_empty() || (title.len() >= 2 && title.starts_with('"') && title.ends_with('"'));
+    quoted.then_some((end, close))
+}
+
+fn markdown_links(md: &str) -> Vec<MdLink> {
+    let verbatim = verbatim_ranges(md);
+    let mut links = Vec::new();
+    let mut from = 0;
+    while let Some(pos) = md[from..].find("](") {
+        let close = from + pos;
+        from = close + 2;
+        if verbatim.iter().any(|r| r.contains(&close)) {
+            continue;
+        }
+        let Some(open) = link_text_start(md, close) else {
+            continue;
+        };
+        let Some((target_end, paren)) = link_target_end(md, close + 2) else {
+            continue;
+        };
+        let image = open > 0 && md.as_bytes()[open - 1] == b'!';
+        let start = if image { open - 1 } else { open };
+        if links.last().is_some_and(|l: &MdLink| l.span.end > start) {
+            continue;
+        }
+        links.push(MdLink {
+            span: start..paren + 1,
+            text: open + 1..close,
+            target: close + 2..target_end,
+            image,
+        });
+        from = paren + 1;
+    }
+    links
+}
+
+/// Removes the backslash escapes `htmd` adds to punctuation in link destinations.
+fn unescape_target(target: &str) -> String {
+    let target = target.trim_start_matches('<').trim_end_matches('>');
+    let mut out = String::with_capacity(target.len());
+    let mut chars = target.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.extend(chars.next()),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Where relative links of a problem point: its own link, or the platform's site when
+/// the backend gave none.
+fn link_base(p: &Problem) -> Option<url::Url> {
+    if let Some(url) = p.link.as_deref().and_then(|l| url::Url::parse(l).ok()) {
+        return Some(url);
+    }
+    let origin = match p.source.to_ascii_lowercase().as_str() {
+        "leetcode" => "https://leetcode.com/",
+        "codeforces" => "https://codeforces.com/",
+        "atcoder" => "https://atcoder.jp/",
+        "luogu" => "https://www.luogu.com.cn/",
+        _ => return None,
+    };
+    url::Url::parse(origin).ok()
+}
+
+/// Query parameters that only track where a visitor came from.
+fn is_tracking_param(key: &str) -> bool {
+    key.starts_with("utm_") || matches!(key, "fbclid" | "gclid" | "mc_cid" | "mc_eid")
+}
+
+enum LinkTarget {
+    /// An absolute http(s) URL, resolved and without tracking parameters.
+    Web(url::Url),
+    /// Another scheme, such as `javascript:` or `data:`.
+    Unsafe,
+    /// Not resolvable: a destination with whitespace, or relative without a base.
+    Other,
+}
+
+fn classify_target(base: Option<&url::Url>, target: &str) -> LinkTarget {
+    let mut url = match url::Url::parse(target) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => url,
+        Ok(_) => return LinkTarget::Unsafe,
+        Err(url::ParseError::RelativeUrlWithoutBase)
+            if !target.is_empty() && !target.contains(char::is_whitespace) =>
+        {
+            match base.and_then(|b| b.join(target).ok()) {
+                Some(url) => url,
+                None => return LinkTarget::Other,
+            }
+        }
+        Err(_) => return LinkTarget::Other,
+    };
+    if url.query_pairs().any(|(k, _)| is_tracking_param(&k)) {
+        let kept: Vec<(String, String)> = url
+            .query_pairs()
+            .filter(|(k, _)| !is_tracking_param(k))
+            .map(|(k, v)| (k.into_owned(), v.into_owned()))
+            .collect();
+        if kept.is_empty() {
+            url.set_query(None);
+        } else {
+            url.query_pairs_mut().clear().extend_pairs(kept);
+        }
+    }
+    LinkTarget::Web(url)
+}
+
+/// Resolves relative link and image destinations against the problem's link, drops
+/// tracking parameters, and replaces links with non-http(s) schemes by their text.
+/// With `strip`, every link is replaced by its text; images are kept.
+fn rewrite_links(md: &str, base: Option<&url::Url>, strip: bool) -> String {
+    let mut out = String::with_capacity(md.len());
+    let mut last = 0;
+    for link in markdown_links(md) {
+        let target = unescape_target(&md[link.target.clone()]);
+        let text = &md[link.text.clone()];
+        let replacement = match classify_target(base, &target) {
+            LinkTarget::Web(_) if strip && !link.image => Some(text.to_owned()),
+            LinkTarget::Web(url) => {
+                let url = url.as_str().replace('(', "%28").replace(')', "%29");
+                let bang = if link.image { "!" } else { "" };
+                Some(format!("{bang}[{text}]({url})"))
+            }
+            LinkTarget::Unsafe => Some(text.to_owned()),
+            LinkTarget::Other => None,
+        };
+        if let Some(replacement) = replacement {
+            out.push_str(&md[last..link.span.start]);
+            out.push_str(&replacement);
+            last = link.span.end;
+        }
+    }
+    out.push_str(&md[last..]);
+    out
+}
+
+/// http(s) images in converted markdown as `(alt, url)`, without duplicates.
+pub fn image_urls(md: &str) -> Vec<(String, String)> {
+    let mut images: Vec<(String, String)> = Vec::new();
+    for link in markdown_links(md).into_iter().filter(|l| l.image) {
+        let url = unescape_target(&md[link.target.clone()]);
+        let is_web = url.starts_with("https://") || url.starts_with("http://");
+        if is_web && !images.iter().any(|(_, u)| *u == url) {
+            images.push((md[link.text.clone()].trim().to_owned(), url));
+        }
     }
+    images
 }
 
 pub fn format_problem(p: &Problem) -> String {
+    format_problem_with(p, &RenderOptions::default())
+}
+
+pub fn format_problem_with(p: &Problem, opts: &RenderOptions) -> String {
     let difficulty = p.difficulty.as_deref().unwrap_or("N/A");
     let tags = match &p.tags {
         Some(v) if !v.is_empty() => v.join(", "),
@@ -38,9 +1443,30 @@ pub fn format_problem(p: &Problem) -> String {
         .ac_rate
         .map(|v| format!("{v:.1}%"))
         .unwrap_or_else(|| "N/A".into());
-    let content = html_to_markdown(p.content.as_deref().unwrap_or(""));
+    let localized = localize(p, opts.lang);
+    let content = if opts.sections.is_empty() {
+        html_to_markdown(&localized.content, opts)
+    } else {
+        format_sections(p, &localized.content, opts)
+    };
+    let cached = p
+        .fetched_at
+        .map(|at| {
+            format!(
+                "- Cached: fetched {} (offline copy, may be stale)\n",
+                at.format("%Y-%m-%d %H:%M UTC")
+            )
+        })
+        .unwrap_or_default();
+    let limits = format_limits(p);
+    let title = if p.io_mode == Some(IoMode::Interactive) {
+        format!("{} (Interactive)", localized.title)
+    } else {
+        localized.title.to_owned()
+    };
+    let language = localized.header;
 
-    format!(
+    let mut out = format!(
         "\
 # {title}
 
@@ -48,14 +1474,336 @@ pub fn format_problem(p: &Problem) -> String {
 - Tags: {tags}
 - Link: {link}
 - AC Rate: {ac_rate}
-
+{language}{limits}{cached}
 ---
 
 {content}",
-        title = p.title,
         source = p.source,
         id = p.id,
-    )
+    );
+    if !out.ends_with('\n') {
+        out.push('\n');
+    }
+
+    if opts.sections.is_empty() {
+        if let Some(hints) = p.hints.as_deref().filter(|h| !h.is_empty()) {
+            out.push_str("\n## Hints\n\n");
+            out.push_str(&format_hints(hints, opts));
+        }
+        if let Some(similar) = p.similar_questions.as_deref().filter(|s| !s.is_empty()) {
+            out.push_str("\n## Similar Questions\n\n");
+            for q in similar {
+                let title = match &q.link {
+                    Some(link) => format!("[{}]({link})", q.title),
+                    None => q.title.clone(),
+                };
+                match &q.difficulty {
+                    Some(d) => out.push_str(&format!("- {title} ({d})\n")),
+                    None => out.push_str(&format!("- {title}\n")),
+                }
+            }
+        }
+    }
+
+    let mut out = rewrite_links(&out, link_base(p).as_ref(), opts.strip_links);
+    let images = image_urls(&out);
+    if !images.is_empty() {
+        out.push_str("\n## Images\n\n");
+        for (i, (alt, url)) in images.iter().enumerate() {
+            if alt.is_empty() {
+                out.push_str(&format!("{}. {url}\n", i + 1));
+            } else {
+                out.push_str(&format!("{}. {url} ({alt})\n", i + 1));
+            }
+        }
+    }
+    out
+}
+
+/// Starter code for `language`, matched on the snippet's slug or display name and then
+/// through aliases such as `c++` or `golang`. A plain `python` means Python 3, which
+/// LeetCode lists as `python3` next to a legacy Python 2 `python` snippet.
+fn find_snippet<'a>(snippets: &'a [CodeSnippet], language: &str) -> Option<&'a CodeSnippet> {
+    let requested = language.trim().to_lowercase();
+    let canonical = canonical_language(&requested);
+    let wanted = if canonical == "python" {
+        "python3"
+    } else {
+        requested.as_str()
+    };
+    snippets
+        .iter()
+        .find(|s| s.lang_slug.eq_ignore_ascii_case(wanted) || s.lang.eq_ignore_ascii_case(wanted))
+        .or_else(|| {
+            snippets
+                .iter()
+                .find(|s| canonical_language(&s.lang_slug.to_lowercase()) == canonical)
+        })
+}
+
+/// `md` followed by the starter code for `language`, or a note listing what is
+/// available. The statement is cut to leave room, so the result fits the output limit
+/// with the starter code intact.
+pub fn with_starter_code(md: String, p: &Problem, language: &str) -> String {
+    let section = starter_code(p, language);
+    let mut md = truncate_to(md, OUTPUT_LIMIT.saturating_sub(section.len() + 1));
+    if !md.ends_with('\n') {
+        md.push('\n');
+    }
+    md.push_str(&section);
+    truncate_output(md)
+}
+
+fn starter_code(p: &Problem, language: &str) -> String {
+    let snippets = p.code_snippets.as_deref().unwrap_or_default();
+    if snippets.is_empty() {
+        return "\n## Starter Code\n\nNo starter code is available for this problem.\n".into();
+    }
+    match find_snippet(snippets, language) {
+        Some(s) => format!(
+            "\n## Starter Code ({})\n\n```{}\n{}\n```\n",
+            s.lang,
+            canonical_language(&s.lang_slug.to_lowercase()),
+            s.code.trim_end()
+        ),
+        None => {
+            let available: Vec<&str> = snippets.iter().map(|s| s.lang_slug.as_str()).collect();
+            format!(
+                "\n## Starter Code\n\nNo starter code for '{}'. Available: {}\n",
+                language.trim(),
+                available.join(", ")
+            )
+        }
+    }
+}
+
+fn selector(s: &str) -> Selector {
+    Selector::parse(s).expect("valid selector")
+}
+
+/// Text of an element as a `<pre>` would show it: `<br>` and block elements end lines.
+fn block_text(el: ElementRef) -> String {
+    let mut out = String::new();
+    for edge in el.traverse() {
+        match edge {
+            Edge::Open(node) => match node.value() {
+                Node::Text(t) => out.push_str(t),
+                Node::Element(e) if e.name() == "br" => out.push('\n'),
+                _ => {}
+            },
+            Edge::Close(node) => {
+                if let Node::Element(e) = node.value()
+                    && matches!(e.name(), "div" | "p" | "li")
+                    && !out.ends_with('\n')
+                {
+                    out.push('\n');
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Input/output/explanation labels used in LeetCode examples, English then Chinese.
+const LEETCODE_LABELS: &[[&str; 3]] = &[
+    ["Input:", "Output:", "Explanation:"],
+    ["输入：", "输出：", "解释："],
+    ["输入:", "输出:", "解释:"],
+];
+
+fn labeled_sample(text: &str) -> Option<Sample> {
+    LEETCODE_LABELS
+        .iter()
+        .find_map(|[input, output, explanation]| {
+            let input_at = text.find(input)?;
+            let output_at = input_at + text[input_at..].find(output)?;
+            let explanation_at = text[output_at..].find(explanation).map(|i| output_at + i);
+            let output_end = explanation_at.unwrap_or(text.len());
+            Some(Sample {
+                input: text[input_at + input.len()..output_at].trim().to_owned(),
+                output: text[output_at + output.len()..output_end].trim().to_owned(),
+                explanation: explanation_at
+                    .map(|at| text[at + explanation.len()..].trim().to_owned())
+                    .filter(|e| !e.is_empty()),
+            })
+        })
+}
+
+/// LeetCode examples: `<pre>` blocks (or newer `div.example-block`s) with
+/// `<strong>Input:</strong>`-style labels.
+fn leetcode_samples(doc: &Html) -> Vec<Sample> {
+    doc.select(&selector("pre, div.example-block"))
+        .filter_map(|el| labeled_sample(&block_text(el)))
+        .collect()
+}
+
+/// Codeforces `div.sample-test` blocks with paired `div.input pre` and `div.output pre`.
+fn codeforces_samples(doc: &Html) -> Vec<Sample> {
+    let (input, output) = (selector("div.input pre"), selector("div.output pre"));
+    doc.select(&selector("div.sample-test"))
+        .flat_map(|test| {
+            let outputs: Vec<String> = test.select(&output).map(block_text).collect();
+            test.select(&input)
+                .map(block_text)
+                .zip(outputs)
+                .map(|(i, o)| Sample {
+                    input: i.trim().to_owned(),
+                    output: o.trim().to_owned(),
+                    explanation: None,
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct SampleParts {
+    input: Option<String>,
+    output: Option<String>,
+    explanation: Option<String>,
+}
+
+/// AtCoder `<h3>Sample Input 1</h3><pre>` sections, falling back to the Japanese
+/// headings when the statement has no English version. Text after a sample output's
+/// `<pre>` is taken as its explanation.
+fn atcoder_samples(doc: &Html) -> Vec<Sample> {
+    let headings = [["Sample Input", "Sample Output"], ["入力例", "出力例"]];
+    let h3 = selector("h3");
+    for [input_label, output_label] in headings {
+        let mut found: BTreeMap<u32, SampleParts> = BTreeMap::new();
+        for heading in doc.select(&h3) {
+            let title = heading.text().collect::<String>();
+            let title = title.trim();
+            let (is_input, number) = match (
+                title.strip_prefix(input_label),
+                title.strip_prefix(output_label),
+            ) {
+                (Some(n), _) => (true, n),
+                (_, Some(n)) => (false, n),
+                _ => continue,
+            };
+            let Ok(number) = number.trim().parse::<u32>() else {
+                continue;
+            };
+            let mut siblings = heading.next_siblings().filter_map(ElementRef::wrap);
+            let Some(pre) = siblings.find(|el| el.value().name() == "pre") else {
+                continue;
+            };
+            let entry = found.entry(number).or_default();
+            if is_input {
+                entry.input = Some(block_text(pre).trim().to_owned());
+            } else {
+                entry.output = Some(block_text(pre).trim().to_owned());
+                let explanation = siblings
+                    .map(|el| el.text().collect::<String>().trim().to_owned())
+                    .filter(|t| !t.is_empty())
+                    .collect::<Vec<_>>()
+                    .join("\n\n");
+                entry.explanation = Some(explanation).filter(|e| !e.is_empty());
+            }
+        }
+        let samples: Vec<Sample> = found
+            .into_values()
+            .filter_map(|parts| {
+                Some(Sample {
+                    input: parts.input?,
+                    output: parts.output?,
+                    explanation: parts.explanation,
+                })
+            })
+            .collect();
+        if !samples.is_empty() {
+            return samples;
+        }
+    }
+    Vec::new()
+}
+
+/// Markdown statements (e.g. Luogu): sample input/output headings, each followed by a
+/// fenced code block.
+fn markdown_samples(content: &str) -> Vec<Sample> {
+    let mut samples = Vec::new();
+    let mut pending_input = None;
+    let mut expecting = None;
+    let mut lines = content.lines();
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') {
+            let lower = trimmed.to_lowercase();
+            let is_sample = ["样例", "sample", "example"]
+                .iter()
+                .any(|w| lower.contains(w));
+            expecting = if !is_sample {
+                None
+            } else if lower.contains("输入") || lower.contains("input") {
+                Some(true)
+            } else if lower.contains("输出") || lower.contains("output") {
+                Some(false)
+            } else {
+                None
+            };
+        } else if trimmed.starts_with("```")
+            && let Some(is_input) = expecting.take()
+        {
+            let block: Vec<&str> = lines
+                .by_ref()
+                .take_while(|l| !l.trim_start().starts_with("```"))
+                .collect();
+            let text = block.join("\n").trim().to_owned();
+            if is_input {
+                pending_input = Some(text);
+            } else if let Some(input) = pending_input.take() {
+                samples.push(Sample {
+                    input,
+                    output: text,
+                    explanation: None,
+                });
+            }
+        }
+    }
+    samples
+}
+
+/// Extracts sample tests from a problem statement using the parser for its platform,
+/// trying the others if that finds nothing.
+pub fn extract_samples(p: &Problem) -> Vec<Sample> {
+    samples_in(&p.source, p.content.as_deref().unwrap_or(""))
+}
+
+/// Samples in `content`, a statement of a problem from `source`.
+fn samples_in(source: &str, content: &str) -> Vec<Sample> {
+    if !looks_like_html(content) {
+        return markdown_samples(content);
+    }
+    let doc = Html::parse_fragment(content);
+    let parsers: [fn(&Html) -> Vec<Sample>; 3] = match source.to_ascii_lowercase().as_str() {
+        "codeforces" => [codeforces_samples, atcoder_samples, leetcode_samples],
+        "atcoder" => [atcoder_samples, codeforces_samples, leetcode_samples],
+        _ => [leetcode_samples, codeforces_samples, atcoder_samples],
+    };
+    parsers
+        .iter()
+        .map(|parse| parse(&doc))
+        .find(|samples| !samples.is_empty())
+        .unwrap_or_default()
+}
+
+pub fn format_sample(index: usize, sample: &Sample) -> String {
+    let mut out = format!(
+        "## Sample {index}\n\n**Input:**\n\n```\n{}\n```\n\n**Output:**\n\n```\n{}\n```\n",
+        sample.input, sample.output
+    );
+    if let Some(explanation) = &sample.explanation {
+        out.push_str(&format!("\n**Explanation:** {explanation}\n"));
+    }
+    out
+}
+
+/// Text made safe for a markdown table cell: `|` escaped and line breaks flattened.
+pub fn table_cell(s: &str) -> String {
+    s.replace('|', "\\|")
+        .replace("\r\n", " ")
+        .replace(['\n', '\r'], " ")
 }
 
 pub fn format_similar(resp: &SimilarResponse) -> String {
@@ -90,6 +1838,88 @@ Query: {}
     out
 }
 
+pub fn format_problem_list(resp: &ProblemListResponse) -> String {
+    let count = match resp.total {
+        Some(total) => format!("{} of {}", resp.problems.len(), format_number(total)),
+        None => resp.problems.len().to_string(),
+    };
+    let mut out = format!(
+        "\
+# Problems ({count})
+
+| # | Source | ID | Title | Difficulty | AC Rate | Tags | Link |
+|---|--------|----|-------|------------|---------|------|------|
+"
+    );
+
+    for (i, p) in resp.problems.iter().enumerate() {
+        let difficulty = p.difficulty.as_deref().unwrap_or("N/A");
+        let ac_rate = p
+            .ac_rate
+            .map(|v| format!("{v:.1}%"))
+            .unwrap_or_else(|| "N/A".into());
+        let tags = match &p.tags {
+            Some(v) if !v.is_empty() => table_cell(&v.join(", ")),
+            _ => "N/A".into(),
+        };
+        let link = p.link.as_deref().unwrap_or("N/A");
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} | {} | {} |\n",
+            i + 1,
+            p.source,
+            table_cell(&p.id),
+            table_cell(&p.title),
+            table_cell(difficulty),
+            ac_rate,
+            tags,
+            link,
+        ));
+    }
+
+    if let Some(cursor) = &resp.next_cursor {
+        out.push_str(&format!(
+            "\nMore results available. Pass cursor `{cursor}` to get the next page.\n"
+        ));
+    }
+
+    out
+}
+
+pub fn format_contest(c: &Contest) -> String {
+    let link = c
+        .link
+        .as_deref()
+        .map(|l| format!("- Link: {l}\n"))
+        .unwrap_or_default();
+    let mut out = format!(
+        "\
+# {}
+
+- Source: {} | Contest: {} | Problems: {}
+{link}
+| # | ID | Title | Difficulty | Link |
+|---|----|-------|------------|------|
+",
+        c.title,
+        c.source,
+        c.id,
+        c.problems.len(),
+    );
+
+    for (i, p) in c.problems.iter().enumerate() {
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            i + 1,
+            table_cell(&p.id),
+            table_cell(&p.title),
+            table_cell(p.difficulty.as_deref().unwrap_or("N/A")),
+            p.link.as_deref().unwrap_or("N/A"),
+        ));
+    }
+
+    out
+}
+
 pub fn format_status(resp: &StatusResponse) -> String {
     let mut out = format!(
         "\
@@ -126,12 +1956,119 @@ pub fn format_number(n: u64) -> String {
     result.chars().rev().collect()
 }
 
+const OUTPUT_LIMIT: usize = 102_400;
+
 pub fn truncate_output(s: String) -> String {
-    if s.len() <= 102_400 {
+    truncate_to(s, OUTPUT_LIMIT)
+}
+
+const TRUNCATION_NOTE: &str = "\n\n... (truncated)";
+
+/// Cuts `s` so that it, including the truncation note, is at most `limit` bytes.
+fn truncate_to(s: String, limit: usize) -> String {
+    if s.len() <= limit {
         return s;
     }
-    let boundary = s.floor_char_boundary(102_400);
+    let boundary = s.floor_char_boundary(limit.saturating_sub(TRUNCATION_NOTE.len()));
     let mut truncated = s[..boundary].to_owned();
-    truncated.push_str("\n\n... (truncated)");
+    truncated.push_str(TRUNCATION_NOTE);
     truncated
 }
+
+/// Truncates a header followed by several parts so that together they fit the output
+/// limit. The header is kept whole when it fits; parts shorter than an even share of
+/// the rest keep their full length and leave the remainder to longer ones.
+pub fn truncate_fair(header: String, parts: Vec<String>) -> Vec<String> {
+    let header = truncate_output(header);
+    let mut order: Vec<usize> = (0..parts.len()).collect();
+    order.sort_by_key(|&i| parts[i].len());
+
+    let mut limits = vec![0; parts.len()];
+    let mut remaining = OUTPUT_LIMIT.saturating_sub(header.len());
+    for (rank, &i) in order.iter().enumerate() {
+        let share = remaining / (parts.len() - rank);
+        limits[i] = parts[i].len().min(share);
+        remaining -= limits[i];
+    }
+
+    std::iter::once(header)
+        .chain(
+            parts
+                .into_iter()
+                .zip(limits)
+                .map(|(part, limit)| truncate_to(part, limit)),
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unicode(md: &str) -> String {
+        normalize_markdown_math(md, MathStyle::Unicode)
+    }
+
+    fn targets(md: &str) -> Vec<&str> {
+        markdown_links(md)
+            .into_iter()
+            .map(|l| &md[l.target])
+            .collect()
+    }
+
+    #[test]
+    fn link_targets_unterminated() {
+        assert!(targets("see [a](\\").is_empty());
+        assert!(targets("see [a](b").is_empty());
+        assert!(targets("see [a](b \"title)").is_empty());
+        assert!(targets("see [a](\\é").is_empty());
+    }
+
+    #[test]
+    fn link_targets_escaped() {
+        assert_eq!(targets(r"[a](x\)y) z"), [r"x\)y"]);
+        assert_eq!(targets(r"[a](x\(y) z"), [r"x\(y"]);
+        assert_eq!(targets(r"[a](\é) z"), [r"\é"]);
+    }
+
+    #[test]
+    fn link_targets_nested_parens() {
+        assert_eq!(targets("[a](f(x)) z"), ["f(x)"]);
+        assert_eq!(targets("[a](f((x))y \"t\") z"), ["f((x))y"]);
+        assert_eq!(targets("![i](a(b).png)"), ["a(b).png"]);
+    }
+
+    #[test]
+    fn starter_code_fits_the_output_limit() {
+        let problem: Problem = serde_json::from_value(serde_json::json!({
+            "source": "leetcode",
+            "id": "two-sum",
+            "title": "Two Sum",
+            "code_snippets": [{ "lang": "C++", "lang_slug": "cpp", "code": "class Solution {};" }],
+        }))
+        .expect("valid problem");
+        let md = with_starter_code("x".repeat(2 * OUTPUT_LIMIT), &problem, "cpp");
+        assert!(md.len() <= OUTPUT_LIMIT);
+        assert!(md.ends_with("```cpp\nclass Solution {};\n```\n"));
+    }
+
+    #[test]
+    fn math_stops_at_code_spans() {
+        assert_eq!(
+            unicode("cost is $5 and $6. `a$b$`"),
+            "cost is $5 and $6. `a$b$`"
+        );
+        assert_eq!(unicode("`$x$` and $n \\le 10$"), "`$x$` and n ≤ 10");
+    }
+
+    #[test]
+    fn math_keeps_currency() {
+        assert_eq!(unicode("pay $5 and $6 now"), "pay $5 and $6 now");
+        assert_eq!(unicode("$n$ costs $3"), "n costs $3");
+    }
+
+    #[test]
+    fn math_skips_escaped_dollars() {
+        assert_eq!(unicode("\\$x\\$ and $y$"), "\\$x\\$ and y");
+    }
+}
@@ -27,3 +27,38 @@ pub fn domain_error(msg: impl Into<String>) -> CallToolResult {
 pub fn protocol_error(msg: impl Into<String>) -> ErrorData {
     ErrorData::internal_error(msg.into(), None)
 }
+
+pub enum FetchError {
+    Domain(String),
+    Protocol(ErrorData),
+}
+
+impl From<ErrorData> for FetchError {
+    fn from(e: ErrorData) -> Self {
+        Self::Protocol(e)
+    }
+}
+
+impl FetchError {
+    pub fn into_tool_result(self) -> Result<CallToolResult, ErrorData> {
+        match self {
+            Self::Domain(msg) => Ok(domain_error(msg)),
+            Self::Protocol(e) => Err(e),
+        }
+    }
+
+    /// Human-readable description, for reporting one failure among several results.
+    pub fn message(&self) -> String {
+        match self {
+            Self::Domain(msg) => msg.clone(),
+            Self::Protocol(e) => e.message.to_string(),
+        }
+    }
+
+    pub fn into_error_data(self) -> ErrorData {
+        match self {
+            Self::Domain(msg) => ErrorData::invalid_params(msg, None),
+            Self::Protocol(e) => e,
+        }
+    }
+}
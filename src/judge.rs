@@ -0,0 +1,429 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::process::Command;
+
+use crate::lang::canonical_language;
+use crate::models::Sample;
+
+const COMPILE_TIMEOUT: Duration = Duration::from_secs(60);
+/// Longest program output kept in a report, in bytes.
+const MAX_REPORTED_OUTPUT: usize = 4096;
+/// Output a program may write to stdout or stderr before it is killed, in bytes.
+const MAX_CAPTURED_OUTPUT: u64 = 16 * 1024 * 1024;
+
+/// How to build and run one language. `{src}` is replaced by the source file, `{exe}`
+/// by a binary path and `{dir}` by the scratch directory, in every argument.
+#[derive(Clone, Deserialize)]
+pub struct LanguageCommands {
+    #[serde(default)]
+    pub compile: Option<String>,
+    pub run: String,
+    /// Apply the memory limit through `ulimit -v`. Runtimes that reserve large address
+    /// spaces up front (JVM, Go, Node.js) fail under it, so they opt out.
+    #[serde(default = "default_true")]
+    pub limit_memory: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Compile/run commands keyed by language name.
+pub struct JudgeConfig {
+    languages: HashMap<String, LanguageCommands>,
+}
+
+const DEFAULT_LANGUAGES: &[(&str, Option<&str>, &str, bool)] = &[
+    (
+        "cpp",
+        Some("g++ -std=c++17 -O2 -o {exe} {src}"),
+        "{exe}",
+        true,
+    ),
+    (
+        "c",
+        Some("gcc -std=c11 -O2 -o {exe} {src} -lm"),
+        "{exe}",
+        true,
+    ),
+    (
+        "rust",
+        Some("rustc --edition 2021 -O -o {exe} {src}"),
+        "{exe}",
+        true,
+    ),
+    ("go", Some("go build -o {exe} {src}"), "{exe}", false),
+    (
+        "java",
+        Some("javac -d {dir} {src}"),
+        "java -cp {dir} Main",
+        false,
+    ),
+    ("python", None, "python3 {src}", true),
+    ("javascript", None, "node {src}", false),
+];
+
+impl Default for JudgeConfig {
+    fn default() -> Self {
+        let languages = DEFAULT_LANGUAGES
+            .iter()
+            .map(|(name, compile, run, limit_memory)| {
+                let commands = LanguageCommands {
+                    compile: compile.map(str::to_owned),
+                    run: (*run).to_owned(),
+                    limit_memory: *limit_memory,
+                };
+                ((*name).to_owned(), commands)
+            })
+            .collect();
+        Self { languages }
+    }
+}
+
+impl JudgeConfig {
+    /// Defaults overridden or extended by a JSON file mapping language names to
+    /// `{ "compile", "run", "limit_memory" }`.
+    pub fn load(path: Option<&Path>) -> Result<Self, String> {
+        let mut config = Self::default();
+        let Some(path) = path else {
+            return Ok(config);
+        };
+        let raw = std::fs::read_to_string(path)
+            .map_err(|e| format!("cannot read judge config {}: {e}", path.display()))?;
+        let overrides: HashMap<String, LanguageCommands> = serde_json::from_str(&raw)
+            .map_err(|e| format!("invalid judge config {}: {e}", path.display()))?;
+        for (name, commands) in overrides {
+            if commands.run.trim().is_empty() {
+                return Err(format!(
+                    "judge config {}: empty run command for '{name}'",
+                    path.display()
+                ));
+            }
+            config.languages.insert(name.to_lowercase(), commands);
+        }
+        Ok(config)
+    }
+
+    /// Canonical language name for `language`, or for the file extension when omitted.
+    pub fn language_for(&self, language: Option<&str>, file: &Path) -> Result<String, String> {
+        let requested = match language.map(str::trim).filter(|l| !l.is_empty()) {
+            Some(l) => l.to_lowercase(),
+            None => file
+                .extension()
+                .and_then(|e| e.to_str())
+                .map(str::to_lowercase)
+                .ok_or("cannot infer the language without a file extension; pass 'language'")?,
+        };
+        let name = canonical_language(&requested);
+        if self.languages.contains_key(name) {
+            return Ok(name.to_owned());
+        }
+        let mut known: Vec<&str> = self.languages.keys().map(String::as_str).collect();
+        known.sort_unstable();
+        Err(format!(
+            "unsupported language '{requested}', configured: {}",
+            known.join(", ")
+        ))
+    }
+
+    fn commands(&self, language: &str) -> &LanguageCommands {
+        &self.languages[language]
+    }
+}
+
+#[derive(Serialize, schemars::JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Passed,
+    WrongAnswer,
+    TimeLimitExceeded,
+    OutputLimitExceeded,
+    RuntimeError,
+}
+
+#[derive(Serialize, schemars::JsonSchema)]
+pub struct TestResult {
+    pub index: usize,
+    pub verdict: Verdict,
+    pub time_ms: u64,
+    pub expected: String,
+    pub actual: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stderr: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<String>,
+}
+
+pub struct Limits {
+    pub time: Duration,
+    pub memory_mb: u64,
+}
+
+/// Scratch directory removed when dropped.
+struct WorkDir(PathBuf);
+
+impl WorkDir {
+    /// Creates a fresh directory under a random name, readable only by the owner on
+    /// Unix. An existing path is never reused, so another user cannot plant files in it.
+    fn new() -> std::io::Result<Self> {
+        let mut builder = std::fs::DirBuilder::new();
+        #[cfg(unix)]
+        std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+        loop {
+            let name = format!("oj-mcp-judge-{:016x}", rand::random::<u64>());
+            let dir = std::env::temp_dir().join(name);
+            match builder.create(&dir) {
+                Ok(()) => return Ok(Self(dir)),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl Drop for WorkDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Compiled (or interpreted) program ready to run against samples.
+pub struct Program {
+    argv: Vec<String>,
+    limit_memory: bool,
+    dir: WorkDir,
+}
+
+impl Program {
+    /// Whether `Limits::memory_mb` is enforced for this program.
+    pub fn limits_memory(&self) -> bool {
+        self.limit_memory && cfg!(unix)
+    }
+}
+
+fn expand(template: &str, src: &Path, dir: &Path) -> Vec<String> {
+    let exe = dir.join("main");
+    template
+        .split_whitespace()
+        .map(|arg| {
+            arg.replace("{src}", &src.to_string_lossy())
+                .replace("{exe}", &exe.to_string_lossy())
+                .replace("{dir}", &dir.to_string_lossy())
+        })
+        .collect()
+}
+
+fn truncate(mut s: String) -> String {
+    if s.len() > MAX_REPORTED_OUTPUT {
+        let mut end = MAX_REPORTED_OUTPUT;
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        s.truncate(end);
+        s.push_str("\n[... truncated]");
+    }
+    s
+}
+
+/// Compiles `src` if the language needs it. A compiler failure is returned as `Err`
+/// with its diagnostics.
+pub async fn prepare(config: &JudgeConfig, language: &str, src: &Path) -> Result<Program, String> {
+    let commands = config.commands(language);
+    let dir = WorkDir::new().map_err(|e| format!("cannot create scratch directory: {e}"))?;
+
+    if let Some(compile) = &commands.compile {
+        let argv = expand(compile, src, &dir.0);
+        let (program, args) = argv.split_first().ok_or("empty compile command")?;
+        let child = Command::new(program)
+            .args(args)
+            .current_dir(&dir.0)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .output();
+        let output = match tokio::time::timeout(COMPILE_TIMEOUT, child).await {
+            Err(_) => {
+                return Err(format!(
+                    "compilation timed out after {}s",
+                    COMPILE_TIMEOUT.as_secs()
+                ));
+            }
+            Ok(Err(e)) => return Err(format!("cannot run '{program}': {e}")),
+            Ok(Ok(output)) => output,
+        };
+        if !output.status.success() {
+            let mut log = String::from_utf8_lossy(&output.stderr).into_owned();
+            log.push_str(&String::from_utf8_lossy(&output.stdout));
+            return Err(truncate(log));
+        }
+    }
+
+    Ok(Program {
+        argv: expand(&commands.run, src, &dir.0),
+        limit_memory: commands.limit_memory,
+        dir,
+    })
+}
+
+/// Lines with trailing whitespace removed and trailing blank lines dropped, the usual
+/// judge comparison.
+fn normalize(s: &str) -> Vec<&str> {
+    let mut lines: Vec<&str> = s.lines().map(str::trim_end).collect();
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+/// Line-by-line diff of the differing lines, `-` expected and `+` actual.
+fn diff(expected: &[&str], actual: &[&str]) -> String {
+    let mut out = String::new();
+    for i in 0..expected.len().max(actual.len()) {
+        let (e, a) = (expected.get(i), actual.get(i));
+        if e == a {
+            continue;
+        }
+        out.push_str(&format!("@@ line {} @@\n", i + 1));
+        if let Some(e) = e {
+            out.push_str(&format!("- {e}\n"));
+        }
+        if let Some(a) = a {
+            out.push_str(&format!("+ {a}\n"));
+        }
+    }
+    truncate(out)
+}
+
+/// Why a run stopped before the program exited on its own.
+enum RunError {
+    Io(std::io::Error),
+    OutputLimit,
+}
+
+/// Reads `pipe` to the end, failing once it exceeds [`MAX_CAPTURED_OUTPUT`] bytes.
+async fn read_capped(pipe: Option<impl AsyncRead + Unpin>) -> Result<Vec<u8>, RunError> {
+    let mut buf = Vec::new();
+    if let Some(pipe) = pipe {
+        pipe.take(MAX_CAPTURED_OUTPUT + 1)
+            .read_to_end(&mut buf)
+            .await
+            .map_err(RunError::Io)?;
+    }
+    if buf.len() as u64 > MAX_CAPTURED_OUTPUT {
+        return Err(RunError::OutputLimit);
+    }
+    Ok(buf)
+}
+
+impl Program {
+    fn command(&self, limits: &Limits) -> Command {
+        let mut cmd = if self.limits_memory() {
+            let mut cmd = Command::new("sh");
+            cmd.arg("-c")
+                .arg(r#"ulimit -v "$1" && shift && exec "$@""#)
+                .arg("sh")
+                .arg((limits.memory_mb * 1024).to_string())
+                .args(&self.argv);
+            cmd
+        } else {
+            let mut cmd = Command::new(&self.argv[0]);
+            cmd.args(&self.argv[1..]);
+            cmd
+        };
+        cmd.current_dir(&self.dir.0)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        cmd
+    }
+
+    pub async fn run(&self, index: usize, sample: &Sample, limits: &Limits) -> TestResult {
+        let mut result = TestResult {
+            index,
+            verdict: Verdict::RuntimeError,
+            time_ms: 0,
+            expected: sample.output.clone(),
+            actual: String::new(),
+            stderr: None,
+            diff: None,
+        };
+        let mut child = match self.command(limits).spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                result.stderr = Some(format!("cannot run '{}': {e}", self.argv[0]));
+                return result;
+            }
+        };
+
+        let mut input = sample.input.clone();
+        input.push('\n');
+        if let Some(mut stdin) = child.stdin.take() {
+            // Programs may exit without reading all input; a broken pipe is not an error.
+            tokio::spawn(async move {
+                let _ = stdin.write_all(input.as_bytes()).await;
+            });
+        }
+
+        let (stdout, stderr) = (child.stdout.take(), child.stderr.take());
+        let start = Instant::now();
+        let outcome = tokio::time::timeout(limits.time, async {
+            let (stdout, stderr) = tokio::try_join!(read_capped(stdout), read_capped(stderr))?;
+            let status = child.wait().await.map_err(RunError::Io)?;
+            Ok((status, stdout, stderr))
+        })
+        .await;
+        result.time_ms = start.elapsed().as_millis() as u64;
+        let (status, stdout, stderr) = match outcome {
+            Ok(Ok(output)) => output,
+            Err(_) => {
+                let _ = child.kill().await;
+                result.verdict = Verdict::TimeLimitExceeded;
+                return result;
+            }
+            Ok(Err(RunError::OutputLimit)) => {
+                let _ = child.kill().await;
+                result.verdict = Verdict::OutputLimitExceeded;
+                result.stderr = Some(format!(
+                    "output exceeded {} MB",
+                    MAX_CAPTURED_OUTPUT / (1024 * 1024)
+                ));
+                return result;
+            }
+            Ok(Err(RunError::Io(e))) => {
+                let _ = child.kill().await;
+                result.stderr = Some(e.to_string());
+                return result;
+            }
+        };
+
+        let stdout = String::from_utf8_lossy(&stdout);
+        let mut stderr = String::from_utf8_lossy(&stderr).trim().to_owned();
+        if !status.success() {
+            if !stderr.is_empty() {
+                stderr.push('\n');
+            }
+            stderr.push_str(&format!("process {status}"));
+        }
+        result.stderr = Some(truncate(stderr)).filter(|s| !s.is_empty());
+        if status.success() {
+            // Compare the full output; only the reported copy is truncated.
+            let (expected, actual) = (normalize(&sample.output), normalize(&stdout));
+            if expected == actual {
+                result.verdict = Verdict::Passed;
+            } else {
+                result.verdict = Verdict::WrongAnswer;
+                result.diff = Some(diff(&expected, &actual));
+            }
+        }
+        result.actual = truncate(stdout.into_owned());
+        result
+    }
+}
@@ -0,0 +1,20 @@
+const ALIASES: &[(&str, &str)] = &[
+    ("c++", "cpp"),
+    ("cc", "cpp"),
+    ("cxx", "cpp"),
+    ("rs", "rust"),
+    ("py", "python"),
+    ("python3", "python"),
+    ("js", "javascript"),
+    ("node", "javascript"),
+    ("golang", "go"),
+];
+
+/// Resolves common aliases (`c++`, `py`, `js`, ...) and file extensions to the language
+/// names shared by the judge, scaffolding and starter-code lookup.
+pub fn canonical_language(name: &str) -> &str {
+    ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map_or(name, |(_, canonical)| canonical)
+}
@@ -1,14 +1,43 @@
+mod cache;
 mod client;
+mod clock;
+mod completion;
 mod convert;
 mod error;
+mod judge;
+mod lang;
 mod models;
+mod origin;
+mod prompts;
+mod resources;
+mod store;
 mod tools;
 
-use clap::Parser;
+use std::net::SocketAddr;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use clap::{Parser, ValueEnum};
 use rmcp::ServiceExt;
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp::transport::{StreamableHttpServerConfig, StreamableHttpService};
+
+use crate::cache::CacheConfig;
+use crate::client::{ClientOptions, OjClient, RetryPolicy};
+use crate::clock::SystemClock;
+use crate::judge::JudgeConfig;
+use crate::origin::HostPolicy;
+use crate::store::StoreConfig;
+use crate::tools::scaffold::ScaffoldConfig;
+use crate::tools::{OjServer, ServerConfig};
 
-use crate::client::OjClient;
-use crate::tools::OjServer;
+#[derive(Clone, Copy, ValueEnum)]
+enum Transport {
+    Stdio,
+    Http,
+}
 
 #[derive(Parser)]
 #[command(version)]
@@ -17,6 +46,64 @@ struct Cli {
     base_url: String,
     #[arg(long)]
     token: Option<String>,
+    #[arg(long, value_enum, default_value = "stdio")]
+    transport: Transport,
+    #[arg(long, default_value = "127.0.0.1:8000")]
+    listen: SocketAddr,
+    /// Additional host name accepted in the Host and Origin headers of HTTP requests,
+    /// besides loopback names and the --listen address; repeatable
+    #[arg(long = "allowed-host")]
+    allowed_hosts: Vec<String>,
+    /// Retries for failed backend requests (connection errors, 429, 502, 503, 504)
+    #[arg(long, default_value_t = 3)]
+    max_retries: u32,
+    /// Initial retry backoff in milliseconds, doubled on each retry with full jitter
+    #[arg(long, default_value_t = 250)]
+    retry_base_ms: u64,
+    /// Give up retrying once a request has taken this many seconds in total
+    #[arg(long, default_value_t = 20)]
+    retry_max_elapsed: u64,
+    /// Maximum number of backend responses kept in the in-memory cache
+    #[arg(long, default_value = "256")]
+    cache_size: NonZeroUsize,
+    /// Seconds to cache problem, resolve, contest and similar-problem responses
+    #[arg(long, default_value_t = 86_400)]
+    cache_problem_ttl: u64,
+    /// Seconds to cache /status and problem list responses
+    #[arg(long, default_value_t = 60)]
+    cache_status_ttl: u64,
+    /// Disable the in-memory response cache
+    #[arg(long)]
+    no_cache: bool,
+    /// Directory where problem, daily, resolve and contest responses are saved for offline use
+    #[arg(long)]
+    cache_dir: Option<PathBuf>,
+    /// Answer only from --cache-dir without contacting the backend
+    #[arg(long, requires = "cache_dir")]
+    offline: bool,
+    /// Upper bound in seconds for the `wait` parameter of get_daily_challenge
+    #[arg(long, default_value_t = 60)]
+    daily_max_wait: u64,
+    /// Register run_samples, which compiles and runs local source files
+    #[arg(long)]
+    enable_judge: bool,
+    /// Also allow run_samples over --transport http, where any client that can reach
+    /// the listener may run code on this machine
+    #[arg(long, requires = "enable_judge")]
+    allow_remote_judge: bool,
+    /// JSON file overriding or adding run_samples compile/run commands per language
+    #[arg(long)]
+    judge_config: Option<PathBuf>,
+    /// Directory scaffold_problem may write under, in addition to the client's roots;
+    /// required for scaffold_problem over --transport http, where client roots are ignored
+    #[arg(long)]
+    workspace_root: Option<PathBuf>,
+    /// Path of a scaffolded problem below the workspace, from {source}, {id} and {slug}
+    #[arg(long, default_value = tools::scaffold::DEFAULT_PATTERN)]
+    scaffold_pattern: String,
+    /// Directory of solution templates named after their language, e.g. cpp.cpp
+    #[arg(long)]
+    template_dir: Option<PathBuf>,
 }
 
 fn validate_base_url(raw: &str) -> Result<String, String> {
@@ -58,6 +145,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             std::process::exit(1);
         }
     };
+    if cli.enable_judge && matches!(cli.transport, Transport::Http) && !cli.allow_remote_judge {
+        eprintln!(
+            "error: --enable-judge over --transport http lets any HTTP client run code on this machine; pass --allow-remote-judge to confirm"
+        );
+        std::process::exit(1);
+    }
 
     tracing_subscriber::fmt()
         .with_env_filter(
@@ -74,14 +167,80 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         tracing::info!("token: not configured");
     }
 
-    let client = match OjClient::new(base_url, cli.token) {
+    let retry = RetryPolicy {
+        max_retries: cli.max_retries,
+        base_delay: Duration::from_millis(cli.retry_base_ms),
+        max_elapsed: Duration::from_secs(cli.retry_max_elapsed),
+    };
+    let cache = (!cli.no_cache).then(|| CacheConfig {
+        capacity: cli.cache_size,
+        problem_ttl: Duration::from_secs(cli.cache_problem_ttl),
+        status_ttl: Duration::from_secs(cli.cache_status_ttl),
+    });
+    if cli.offline {
+        tracing::info!("offline mode: answering from the cache directory only");
+    }
+    let store = cli.cache_dir.map(|dir| StoreConfig {
+        dir,
+        offline: cli.offline,
+    });
+    let options = ClientOptions {
+        token: cli.token,
+        retry,
+        cache,
+        store,
+        clock: Arc::new(SystemClock),
+    };
+    let client = match OjClient::new(base_url, options) {
         Ok(c) => c,
         Err(e) => {
             eprintln!("error: {e}");
             std::process::exit(1);
         }
     };
-    let server = OjServer::new(client);
+    let judge = match JudgeConfig::load(cli.judge_config.as_deref()) {
+        Ok(j) => j,
+        Err(e) => {
+            eprintln!("error: {e}");
+            std::process::exit(1);
+        }
+    };
+    // A remote client's roots say nothing about this machine, so over HTTP only
+    // --workspace-root is writable and the tool is disabled without it.
+    let local = matches!(cli.transport, Transport::Stdio);
+    let scaffold = if local || cli.workspace_root.is_some() {
+        match ScaffoldConfig::new(
+            cli.workspace_root,
+            local,
+            cli.scaffold_pattern,
+            cli.template_dir.as_deref(),
+        ) {
+            Ok(s) => Some(s),
+            Err(e) => {
+                eprintln!("error: {e}");
+                std::process::exit(1);
+            }
+        }
+    } else {
+        tracing::info!("scaffold_problem disabled: --transport http requires --workspace-root");
+        None
+    };
+    let config = ServerConfig {
+        daily_max_wait: Duration::from_secs(cli.daily_max_wait),
+        judge: cli.enable_judge.then_some(judge),
+        scaffold,
+    };
+    let server = OjServer::new(client, config);
+    match cli.transport {
+        Transport::Stdio => serve_stdio(server).await,
+        Transport::Http => {
+            let policy = HostPolicy::new(cli.listen, &cli.allowed_hosts);
+            serve_http(server, cli.listen, policy).await
+        }
+    }
+}
+
+async fn serve_stdio(server: OjServer) -> Result<(), Box<dyn std::error::Error>> {
     let service = server
         .serve(rmcp::transport::io::stdio())
         .await
@@ -90,3 +249,34 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+async fn serve_http(
+    server: OjServer,
+    listen: SocketAddr,
+    policy: HostPolicy,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let config = StreamableHttpServerConfig::default();
+    let shutdown = config.cancellation_token.clone();
+    let service = StreamableHttpService::new(
+        move || Ok(server.clone()),
+        Arc::new(LocalSessionManager::default()),
+        config,
+    );
+    let router = axum::Router::new().nest_service("/mcp", service).layer(
+        axum::middleware::from_fn_with_state(Arc::new(policy), origin::guard),
+    );
+
+    let listener = tokio::net::TcpListener::bind(listen)
+        .await
+        .inspect_err(|e| tracing::error!("bind {listen} failed: {e}"))?;
+    tracing::info!("listening on http://{listen}/mcp");
+
+    axum::serve(listener, router)
+        .with_graceful_shutdown(async move {
+            let _ = tokio::signal::ctrl_c().await;
+            shutdown.cancel();
+        })
+        .await?;
+
+    Ok(())
+}
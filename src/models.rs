@@ -1,6 +1,7 @@
-use serde::Deserialize;
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
 pub struct Problem {
     pub id: String,
     #[serde(default)]
@@ -14,8 +15,116 @@ pub struct Problem {
     pub tags: Option<Vec<String>>,
     #[serde(default)]
     pub link: Option<String>,
-    #[serde(default)]
+    /// Raw statement HTML or markdown. Left out of structured output, which would
+    /// otherwise repeat the whole body next to the rendered (and truncated) text.
+    #[serde(default, skip_serializing)]
+    #[schemars(skip)]
     pub content: Option<String>,
+    /// Chinese title from LeetCode CN.
+    #[serde(default)]
+    pub translated_title: Option<String>,
+    /// Chinese statement from LeetCode CN, left out of structured output like `content`.
+    #[serde(default, skip_serializing)]
+    #[schemars(skip)]
+    pub translated_content: Option<String>,
+    /// Per-language starter code (LeetCode).
+    #[serde(default)]
+    pub code_snippets: Option<Vec<CodeSnippet>>,
+    #[serde(default)]
+    pub hints: Option<Vec<String>>,
+    #[serde(default)]
+    pub similar_questions: Option<Vec<SimilarQuestion>>,
+    /// Example test case inputs, one entry per example (LeetCode).
+    #[serde(default)]
+    pub examples: Option<Vec<String>>,
+    #[serde(default)]
+    pub time_limit_ms: Option<u64>,
+    #[serde(default)]
+    pub memory_limit_mb: Option<u64>,
+    #[serde(default)]
+    pub io_mode: Option<IoMode>,
+    /// Whether answers are checked by a special judge, e.g. any valid answer is accepted.
+    #[serde(default)]
+    pub special_judge: Option<bool>,
+    /// When this copy was fetched, set only when served from the offline store.
+    #[serde(skip)]
+    pub fetched_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// How a program receives input and produces output.
+#[derive(Deserialize, Serialize, schemars::JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum IoMode {
+    /// Standard input and output.
+    #[serde(alias = "standard", alias = "stdio")]
+    Stdin,
+    /// Named input and output files.
+    File,
+    /// Dialogue with an interactor through standard input and output.
+    Interactive,
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+pub struct CodeSnippet {
+    /// Display name, e.g. `C++` or `Python3`.
+    pub lang: String,
+    /// Language slug, e.g. `cpp` or `python3`.
+    pub lang_slug: String,
+    pub code: String,
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+pub struct SimilarQuestion {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub difficulty: Option<String>,
+    #[serde(default)]
+    pub link: Option<String>,
+}
+
+/// One sample test case extracted from a problem statement.
+#[derive(Serialize, schemars::JsonSchema)]
+pub struct Sample {
+    pub input: String,
+    pub output: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+pub struct ProblemListResponse {
+    pub problems: Vec<ProblemSummary>,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+    #[serde(default)]
+    pub total: Option<u64>,
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+pub struct ProblemSummary {
+    pub source: String,
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub difficulty: Option<String>,
+    #[serde(default)]
+    pub ac_rate: Option<f64>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub link: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+pub struct Contest {
+    pub source: String,
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub link: Option<String>,
+    /// Problems in contest order.
+    pub problems: Vec<ProblemSummary>,
 }
 
 #[derive(Deserialize)]
@@ -23,13 +132,13 @@ pub struct DailyFetching {
     pub retry_after: u64,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
 pub struct SimilarResponse {
     pub rewritten_query: String,
     pub results: Vec<SimilarResult>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
 pub struct SimilarResult {
     pub source: String,
     pub id: String,
@@ -46,13 +155,13 @@ pub struct ResolveResponse {
     pub problem: Problem,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
 pub struct StatusResponse {
     pub version: String,
     pub platforms: Vec<PlatformStatus>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
 pub struct PlatformStatus {
     pub source: String,
     pub total: u64,
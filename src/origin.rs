@@ -0,0 +1,71 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::extract::State;
+use axum::http::header::{HOST, ORIGIN};
+use axum::http::{HeaderMap, Request, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+/// Host names the HTTP transport answers to: loopback names, the `--listen` address and
+/// any configured with `--allowed-host`. Checking `Host` and `Origin` against them keeps
+/// web pages from reaching the server through DNS rebinding or cross-origin requests.
+pub struct HostPolicy {
+    allowed: Vec<String>,
+}
+
+impl HostPolicy {
+    pub fn new(listen: SocketAddr, extra: &[String]) -> Self {
+        let mut allowed: Vec<String> = ["localhost", "127.0.0.1", "::1"]
+            .iter()
+            .map(|h| (*h).to_owned())
+            .collect();
+        if !listen.ip().is_unspecified() {
+            allowed.push(listen.ip().to_string());
+        }
+        allowed.extend(extra.iter().map(|h| h.trim().to_ascii_lowercase()));
+        Self { allowed }
+    }
+
+    fn allows(&self, host: &str) -> bool {
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        self.allowed.iter().any(|h| h.eq_ignore_ascii_case(host))
+    }
+
+    /// Why the request is refused, if it is. Requests without `Origin` come from
+    /// non-browser clients and only need an allowed `Host`.
+    fn check(&self, headers: &HeaderMap) -> Option<String> {
+        if let Some(host) = headers.get(HOST) {
+            let host = host.to_str().unwrap_or_default();
+            let authority = url::Url::parse(&format!("http://{host}")).ok();
+            let name = authority.as_ref().and_then(|u| u.host_str());
+            if !name.is_some_and(|n| self.allows(n)) {
+                return Some(format!("host '{host}' is not allowed"));
+            }
+        }
+        if let Some(origin) = headers.get(ORIGIN) {
+            let origin = origin.to_str().unwrap_or_default();
+            let url = url::Url::parse(origin).ok();
+            let name = url.as_ref().and_then(|u| u.host_str());
+            if !name.is_some_and(|n| self.allows(n)) {
+                return Some(format!("origin '{origin}' is not allowed"));
+            }
+        }
+        None
+    }
+}
+
+pub async fn guard(
+    State(policy): State<Arc<HostPolicy>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    match policy.check(request.headers()) {
+        Some(reason) => {
+            tracing::warn!("rejected HTTP request: {reason}");
+            (StatusCode::FORBIDDEN, reason).into_response()
+        }
+        None => next.run(request).await,
+    }
+}
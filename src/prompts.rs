@@ -0,0 +1,155 @@
+use rmcp::model::{ErrorData, GetPromptResult, PromptMessage, PromptMessageRole};
+use rmcp::schemars;
+use serde::Deserialize;
+
+use crate::client::OjClient;
+use crate::convert::{format_problem, truncate_output};
+use crate::error::FetchError;
+use crate::models::Problem;
+use crate::tools::daily::{self, DailyOutcome, Domain};
+use crate::tools::problem;
+
+#[derive(Deserialize, schemars::JsonSchema)]
+pub struct SolveParams {
+    #[schemars(description = "Problem source: leetcode, codeforces, atcoder, or luogu")]
+    pub source: String,
+    #[schemars(description = "Problem ID on the platform, e.g. '1', '1A', 'abc001_1', 'P1001'")]
+    pub id: String,
+    #[serde(default)]
+    #[schemars(description = "Programming language for the solution (default: C++)")]
+    pub language: Option<String>,
+}
+
+#[derive(Deserialize, schemars::JsonSchema)]
+pub struct ExplainParams {
+    #[schemars(description = "Problem source: leetcode, codeforces, atcoder, or luogu")]
+    pub source: String,
+    #[schemars(description = "Problem ID on the platform, e.g. '1', '1A', 'abc001_1', 'P1001'")]
+    pub id: String,
+}
+
+#[derive(Deserialize, schemars::JsonSchema)]
+pub struct ReviewParams {
+    #[schemars(description = "Problem source: leetcode, codeforces, atcoder, or luogu")]
+    pub source: String,
+    #[schemars(description = "Problem ID on the platform, e.g. '1', '1A', 'abc001_1', 'P1001'")]
+    pub id: String,
+    #[schemars(description = "The solution source code to review")]
+    pub code: String,
+    #[serde(default)]
+    #[schemars(description = "Programming language of the solution")]
+    pub language: Option<String>,
+}
+
+#[derive(Deserialize, schemars::JsonSchema)]
+pub struct DailyPracticeParams {
+    #[serde(default)]
+    #[schemars(description = "LeetCode domain: 'com' (default) or 'cn'")]
+    pub domain: Option<String>,
+    #[serde(default)]
+    #[schemars(description = "Programming language for the solution (default: C++)")]
+    pub language: Option<String>,
+}
+
+fn prompt(description: String, instructions: &str, problem: &Problem) -> GetPromptResult {
+    let statement = truncate_output(format_problem(problem));
+    GetPromptResult {
+        description: Some(description),
+        messages: vec![PromptMessage::new_text(
+            PromptMessageRole::User,
+            format!("{instructions}\n\n{statement}"),
+        )],
+    }
+}
+
+async fn fetch_problem(client: &OjClient, source: &str, id: &str) -> Result<Problem, ErrorData> {
+    problem::fetch(client, source, id)
+        .await
+        .map_err(FetchError::into_error_data)
+}
+
+pub async fn solve_problem(
+    client: &OjClient,
+    params: SolveParams,
+) -> Result<GetPromptResult, ErrorData> {
+    let problem = fetch_problem(client, &params.source, &params.id).await?;
+    let language = params.language.as_deref().unwrap_or("C++");
+    let instructions = format!(
+        "Solve the following competitive programming problem in {language}. \
+         Start with the key observations, state the algorithm and its time and space complexity, \
+         then give a complete, compilable solution that reads the input and writes the output exactly as specified."
+    );
+    Ok(prompt(
+        format!("Solve {} in {language}", problem.title),
+        &instructions,
+        &problem,
+    ))
+}
+
+pub async fn explain_approach(
+    client: &OjClient,
+    params: ExplainParams,
+) -> Result<GetPromptResult, ErrorData> {
+    let problem = fetch_problem(client, &params.source, &params.id).await?;
+    let instructions = "Explain how to approach the following problem without writing a full solution. \
+         Restate the task briefly, point out the constraints that determine the intended complexity, \
+         walk through the key insight step by step, and mention common pitfalls and edge cases.";
+    Ok(prompt(
+        format!("Explain the approach to {}", problem.title),
+        instructions,
+        &problem,
+    ))
+}
+
+pub async fn review_my_solution(
+    client: &OjClient,
+    params: ReviewParams,
+) -> Result<GetPromptResult, ErrorData> {
+    let problem = fetch_problem(client, &params.source, &params.id).await?;
+    let language = params.language.as_deref().unwrap_or("");
+    let instructions = format!(
+        "Review my solution to the following problem. Check correctness against the statement and constraints, \
+         look for edge cases it misses, estimate its time and space complexity, and suggest concrete improvements.\n\n\
+         My solution:\n\n```{language}\n{}\n```",
+        params.code.trim_end()
+    );
+    Ok(prompt(
+        format!("Review a solution to {}", problem.title),
+        &instructions,
+        &problem,
+    ))
+}
+
+pub async fn daily_practice(
+    client: &OjClient,
+    params: DailyPracticeParams,
+) -> Result<GetPromptResult, ErrorData> {
+    let domain = match params.domain.as_deref().map(str::trim) {
+        None | Some("") => Domain::default(),
+        Some(d) => d
+            .parse::<Domain>()
+            .map_err(|e| ErrorData::invalid_params(e, None))?,
+    };
+    let problem = match daily::fetch(client, domain, None).await {
+        Ok(DailyOutcome::Ready(p)) => *p,
+        // The request was valid; the backend just is not ready yet.
+        Ok(DailyOutcome::Fetching(retry_after)) => {
+            return Err(ErrorData::internal_error(
+                daily::fetching_message(retry_after),
+                None,
+            ));
+        }
+        Err(e) => return Err(e.into_error_data()),
+    };
+    let language = params.language.as_deref().unwrap_or("C++");
+    let instructions = format!(
+        "Here is today's LeetCode daily challenge. Coach me through it in {language}: \
+         first give a hint toward the key idea without revealing the full solution, \
+         then wait for my attempt before showing the complete code."
+    );
+    Ok(prompt(
+        format!("Daily practice: {}", problem.title),
+        &instructions,
+        &problem,
+    ))
+}
@@ -0,0 +1,126 @@
+use rmcp::model::{
+    AnnotateAble, Content, ErrorData, RawResource, RawResourceTemplate, ReadResourceResult,
+    Resource, ResourceContents, ResourceTemplate,
+};
+
+use crate::client::OjClient;
+use crate::convert::{format_problem, format_status, truncate_output};
+use crate::error::FetchError;
+use crate::models::Problem;
+use crate::tools::daily::{self, DailyOutcome, Domain};
+use crate::tools::{problem, status};
+
+const MARKDOWN: &str = "text/markdown";
+pub const STATUS_URI: &str = "oj://status";
+
+pub fn problem_uri(source: &str, id: &str) -> String {
+    format!(
+        "oj://problem/{}/{}",
+        urlencoding::encode(source),
+        urlencoding::encode(id)
+    )
+}
+
+pub fn problem_link(p: &Problem) -> Content {
+    let mut res = RawResource::new(
+        problem_uri(&p.source, &p.id),
+        format!("{}/{}", p.source, p.id),
+    );
+    res.title = Some(p.title.clone());
+    res.mime_type = Some(MARKDOWN.into());
+    Content::resource_link(res)
+}
+
+pub fn list() -> Vec<Resource> {
+    let mut status = RawResource::new(STATUS_URI, "status");
+    status.title = Some("OJ Platform Status".into());
+    status.description = Some("Problem counts and indexing coverage for each platform".into());
+    status.mime_type = Some(MARKDOWN.into());
+    vec![status.no_annotation()]
+}
+
+pub fn templates() -> Vec<ResourceTemplate> {
+    let template = |uri: &str, name: &str, title: &str, description: &str| {
+        RawResourceTemplate {
+            uri_template: uri.into(),
+            name: name.into(),
+            title: Some(title.into()),
+            description: Some(description.into()),
+            mime_type: Some(MARKDOWN.into()),
+            icons: None,
+        }
+        .no_annotation()
+    };
+    vec![
+        template(
+            "oj://problem/{source}/{id}",
+            "problem",
+            "Problem",
+            "A problem by source (leetcode, codeforces, atcoder, luogu) and platform ID",
+        ),
+        template(
+            "oj://daily/{domain}/{date}",
+            "daily",
+            "LeetCode Daily Challenge",
+            "The LeetCode daily challenge for a domain ('com' or 'cn') and date (YYYY-MM-DD)",
+        ),
+    ]
+}
+
+enum ResourceUri {
+    Problem { source: String, id: String },
+    Daily { domain: Domain, date: String },
+    Status,
+}
+
+fn decode(segment: &str) -> Option<String> {
+    let s = urlencoding::decode(segment).ok()?.into_owned();
+    (!s.is_empty()).then_some(s)
+}
+
+fn parse_uri(uri: &str) -> Result<ResourceUri, String> {
+    let rest = uri
+        .strip_prefix("oj://")
+        .ok_or_else(|| format!("unsupported resource URI '{uri}'"))?;
+    let segments: Vec<&str> = rest.trim_end_matches('/').split('/').collect();
+    let parsed = match segments.as_slice() {
+        ["status"] => Some(ResourceUri::Status),
+        ["problem", source, id] => decode(source)
+            .zip(decode(id))
+            .map(|(source, id)| ResourceUri::Problem { source, id }),
+        ["daily", domain, date] => {
+            let domain = domain.parse::<Domain>()?;
+            decode(date).map(|date| ResourceUri::Daily { domain, date })
+        }
+        _ => None,
+    };
+    parsed.ok_or_else(|| format!("unknown resource URI '{uri}'"))
+}
+
+pub async fn read(client: &OjClient, uri: &str) -> Result<ReadResourceResult, ErrorData> {
+    let parsed = parse_uri(uri).map_err(|e| ErrorData::resource_not_found(e, None))?;
+
+    let rendered =
+        match parsed {
+            ResourceUri::Problem { source, id } => problem::fetch(client, &source, &id)
+                .await
+                .map(|p| format_problem(&p)),
+            ResourceUri::Daily { domain, date } => daily::fetch(client, domain, Some(&date))
+                .await
+                .map(|outcome| match outcome {
+                    DailyOutcome::Ready(p) => format_problem(&p),
+                    DailyOutcome::Fetching(retry_after) => daily::fetching_message(retry_after),
+                }),
+            ResourceUri::Status => status::fetch(client).await.map(|s| format_status(&s)),
+        };
+    let md = rendered.map_err(FetchError::into_error_data)?;
+
+    Ok(ReadResourceResult {
+        contents: vec![ResourceContents::TextResourceContents {
+            uri: uri.to_owned(),
+            mime_type: Some(MARKDOWN.into()),
+            text: truncate_output(md),
+            meta: None,
+        }],
+    })
+}
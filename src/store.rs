@@ -0,0 +1,130 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::client::RawResponse;
+
+const MAX_FILE_NAME: usize = 200;
+
+pub struct StoreConfig {
+    pub dir: PathBuf,
+    /// Answer only from the store and never contact the backend.
+    pub offline: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredEntry {
+    path: String,
+    fetched_at: DateTime<Utc>,
+    body: String,
+}
+
+/// Persistent on-disk copy of problem, daily, resolve and contest responses, one JSON file
+/// per path.
+pub struct DiskStore {
+    dir: PathBuf,
+}
+
+/// Whether responses for this request path are kept on disk.
+pub fn is_persisted(path: &str) -> bool {
+    path.starts_with("/api/v1/problems/")
+        || path.starts_with("/api/v1/daily?")
+        || path.starts_with("/api/v1/resolve/")
+        || path.starts_with("/api/v1/contests/")
+}
+
+/// 64-bit FNV-1a, stable across builds so file names survive upgrades.
+fn fnv1a(s: &str) -> u64 {
+    s.bytes().fold(0xcbf2_9ce4_8422_2325, |h, b| {
+        (h ^ u64::from(b)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+impl DiskStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn file_for(&self, path: &str) -> PathBuf {
+        let encoded = urlencoding::encode(path);
+        let name = if encoded.len() <= MAX_FILE_NAME {
+            format!("{encoded}.json")
+        } else {
+            format!("{:016x}.json", fnv1a(path))
+        };
+        self.dir.join(name)
+    }
+
+    pub async fn load(&self, path: &str) -> Option<RawResponse> {
+        let raw = tokio::fs::read(self.file_for(path)).await.ok()?;
+        let entry: StoredEntry = serde_json::from_slice(&raw).ok()?;
+        // Guards against hashed file names colliding.
+        (entry.path == path).then(|| entry.into_response())
+    }
+
+    /// Every stored response whose request path starts with `prefix`.
+    pub async fn load_prefix(&self, prefix: &str) -> Vec<RawResponse> {
+        let mut found = Vec::new();
+        let Ok(mut dir) = tokio::fs::read_dir(&self.dir).await else {
+            return found;
+        };
+        while let Ok(Some(file)) = dir.next_entry().await {
+            if file.path().extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let Ok(raw) = tokio::fs::read(file.path()).await else {
+                continue;
+            };
+            if let Ok(entry) = serde_json::from_slice::<StoredEntry>(&raw)
+                && entry.path.starts_with(prefix)
+            {
+                found.push(entry.into_response());
+            }
+        }
+        found
+    }
+
+    pub async fn save(&self, path: &str, response: &RawResponse) {
+        if response.status != 200 || !response.is_json || !is_persisted(path) {
+            return;
+        }
+        let entry = StoredEntry {
+            path: path.to_owned(),
+            fetched_at: response.fetched_at.unwrap_or_else(Utc::now),
+            body: response.body.clone(),
+        };
+        if let Err(e) = self.write(path, &entry).await {
+            tracing::warn!("cache write for {path} failed: {e}");
+        }
+    }
+
+    async fn write(&self, path: &str, entry: &StoredEntry) -> std::io::Result<()> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let file = self.file_for(path);
+        // Unique per writer, so concurrent saves of one path never share a temp file.
+        static NEXT_TMP: AtomicU64 = AtomicU64::new(0);
+        let n = NEXT_TMP.fetch_add(1, Ordering::Relaxed);
+        let tmp = file.with_extension(format!("json.{}-{n}.tmp", std::process::id()));
+        let written = match tokio::fs::write(&tmp, serde_json::to_vec(entry)?).await {
+            Ok(()) => tokio::fs::rename(&tmp, &file).await,
+            Err(e) => Err(e),
+        };
+        if written.is_err() {
+            let _ = tokio::fs::remove_file(&tmp).await;
+        }
+        written
+    }
+}
+
+impl StoredEntry {
+    fn into_response(self) -> RawResponse {
+        RawResponse {
+            status: 200,
+            body: self.body,
+            is_json: true,
+            fetched_at: Some(self.fetched_at),
+        }
+    }
+}
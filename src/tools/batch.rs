@@ -0,0 +1,149 @@
+use futures::StreamExt;
+use rmcp::model::{CallToolResult, Content, ErrorData};
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+
+use crate::client::OjClient;
+use crate::convert::{format_problem, truncate_fair};
+use crate::error::domain_error;
+use crate::models::Problem;
+use crate::resources::problem_link;
+
+use super::{problem, resolve, structured_success};
+
+const MAX_ITEMS: usize = 30;
+const MAX_CONCURRENCY: usize = 4;
+
+#[derive(Deserialize, schemars::JsonSchema)]
+pub struct BatchItem {
+    #[serde(default)]
+    #[schemars(description = "Problem source: leetcode, codeforces, atcoder, or luogu")]
+    pub source: Option<String>,
+
+    #[serde(default)]
+    #[schemars(description = "Problem ID on the platform, e.g. '1', '1A', 'abc001_1', 'P1001'")]
+    pub id: Option<String>,
+
+    #[serde(default)]
+    #[schemars(
+        description = "A problem URL, slug, or prefixed ID resolved like resolve_problem (takes priority over source+id)"
+    )]
+    pub query: Option<String>,
+}
+
+#[derive(Deserialize, schemars::JsonSchema)]
+pub struct GetProblemsParams {
+    #[schemars(
+        description = "Problems to fetch (1-30), each either {source, id} or {query}. Results keep this order"
+    )]
+    pub items: Vec<BatchItem>,
+}
+
+#[derive(Serialize, schemars::JsonSchema)]
+pub struct BatchItemResult {
+    pub index: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub problem: Option<Problem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, schemars::JsonSchema)]
+pub struct BatchOutput {
+    pub fetched: usize,
+    pub failed: usize,
+    pub results: Vec<BatchItemResult>,
+}
+
+impl BatchItem {
+    fn label(&self) -> String {
+        match (&self.query, &self.source, &self.id) {
+            (Some(q), _, _) if !q.trim().is_empty() => q.trim().to_owned(),
+            (_, Some(s), Some(id)) => format!("{}/{}", s.trim(), id.trim()),
+            _ => "(empty item)".into(),
+        }
+    }
+}
+
+async fn fetch_item(client: &OjClient, item: &BatchItem) -> Result<Problem, String> {
+    let query = item.query.as_deref().map(str::trim).unwrap_or("");
+    let fetched = if !query.is_empty() {
+        resolve::fetch(client, query).await
+    } else {
+        let (Some(source), Some(id)) = (&item.source, &item.id) else {
+            return Err("either 'query' or both 'source' and 'id' must be provided".into());
+        };
+        problem::fetch(client, source, id).await
+    };
+    fetched.map_err(|e| e.message())
+}
+
+pub async fn run(
+    client: &OjClient,
+    params: GetProblemsParams,
+) -> Result<CallToolResult, ErrorData> {
+    let items = params.items;
+    if items.is_empty() || items.len() > MAX_ITEMS {
+        return Ok(domain_error(format!(
+            "items must contain between 1 and {MAX_ITEMS} entries"
+        )));
+    }
+
+    let fetched: Vec<Result<Problem, String>> = futures::stream::iter(0..items.len())
+        .map(|i| fetch_item(client, &items[i]))
+        .buffered(MAX_CONCURRENCY)
+        .collect()
+        .await;
+
+    let mut errors = Vec::new();
+    let mut sections = Vec::new();
+    let mut links = Vec::new();
+    for (i, (item, result)) in items.iter().zip(&fetched).enumerate() {
+        match result {
+            Ok(p) => {
+                sections.push(format_problem(p));
+                links.push(problem_link(p));
+            }
+            Err(e) => errors.push(format!("- #{} {}: {e}", i + 1, item.label())),
+        }
+    }
+
+    let mut md = format!(
+        "# Problems ({} of {} fetched)\n",
+        sections.len(),
+        items.len()
+    );
+    if !errors.is_empty() {
+        md.push_str("\n## Errors\n\n");
+        md.push_str(&errors.join("\n"));
+        md.push('\n');
+    }
+    let mut content: Vec<Content> = truncate_fair(md, sections)
+        .into_iter()
+        .map(Content::text)
+        .collect();
+    content.extend(links);
+
+    let results: Vec<BatchItemResult> = fetched
+        .into_iter()
+        .enumerate()
+        .map(|(index, result)| match result {
+            Ok(p) => BatchItemResult {
+                index,
+                problem: Some(p),
+                error: None,
+            },
+            Err(e) => BatchItemResult {
+                index,
+                problem: None,
+                error: Some(e),
+            },
+        })
+        .collect();
+    let output = BatchOutput {
+        fetched: results.iter().filter(|r| r.problem.is_some()).count(),
+        failed: errors.len(),
+        results,
+    };
+    structured_success(&output, content)
+}
@@ -0,0 +1,152 @@
+use futures::StreamExt;
+use rmcp::model::{CallToolResult, Content, ErrorData};
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+
+use crate::client::OjClient;
+use crate::convert::{format_contest, format_problem, truncate_fair};
+use crate::error::FetchError;
+use crate::models::{Contest, Problem};
+use crate::resources::problem_link;
+
+use super::{problem, structured_success};
+
+const MAX_CONCURRENCY: usize = 4;
+const ATCODER_SERIES: &[&str] = &["abc", "arc", "agc", "ahc"];
+
+#[derive(Deserialize, schemars::JsonSchema)]
+pub struct GetContestParams {
+    #[schemars(
+        description = "Contest identifier: a Codeforces round number ('1900'), an AtCoder contest ID ('abc300'), or a contest URL"
+    )]
+    pub contest: String,
+
+    #[serde(default)]
+    #[schemars(
+        description = "Platform of the contest: codeforces or atcoder. Detected from the identifier if omitted"
+    )]
+    pub source: Option<String>,
+
+    #[serde(default)]
+    #[schemars(
+        description = "Also fetch and include every problem's full statement (default: false)"
+    )]
+    pub include_statements: Option<bool>,
+}
+
+#[derive(Serialize, schemars::JsonSchema)]
+pub struct ContestOutput {
+    pub contest: Contest,
+    /// Full problems in contest order, present when statements were requested.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub statements: Vec<Problem>,
+    /// Problems whose statement could not be fetched.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+/// Splits a contest identifier into `(source, contest_id)`.
+fn parse_contest(input: &str, source: Option<&str>) -> Result<(String, String), FetchError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(FetchError::Domain("contest must be non-empty".into()));
+    }
+
+    if let Ok(url) = url::Url::parse(input) {
+        let host = url.host_str().unwrap_or("").trim_start_matches("www.");
+        let segments: Vec<&str> = url.path_segments().into_iter().flatten().collect();
+        return match (host, segments.as_slice()) {
+            ("codeforces.com", ["contest", id, ..]) => Ok(("codeforces".into(), (*id).into())),
+            ("atcoder.jp", ["contests", id, ..]) => Ok(("atcoder".into(), (*id).into())),
+            _ => Err(FetchError::Domain(format!(
+                "unsupported contest URL '{input}', expected codeforces.com/contest/... or atcoder.jp/contests/..."
+            ))),
+        };
+    }
+
+    if let Some(source) = source.map(str::trim).filter(|s| !s.is_empty()) {
+        return Ok((source.to_lowercase(), input.to_owned()));
+    }
+
+    let lower = input.to_lowercase();
+    let is_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    if is_digits(&lower) {
+        Ok(("codeforces".into(), lower))
+    } else if let Some(round) = lower.strip_prefix("cf")
+        && is_digits(round)
+    {
+        Ok(("codeforces".into(), round.into()))
+    } else if ATCODER_SERIES
+        .iter()
+        .any(|series| lower.strip_prefix(series).is_some_and(is_digits))
+    {
+        Ok(("atcoder".into(), lower))
+    } else {
+        Err(FetchError::Domain(format!(
+            "cannot detect the platform of contest '{input}'; pass 'source' or a contest URL"
+        )))
+    }
+}
+
+pub async fn fetch(
+    client: &OjClient,
+    contest: &str,
+    source: Option<&str>,
+) -> Result<Contest, FetchError> {
+    let (source, id) = parse_contest(contest, source)?;
+    let path = format!(
+        "/api/v1/contests/{}/{}",
+        urlencoding::encode(&source),
+        urlencoding::encode(&id)
+    );
+    let contest: Contest = client.get_json(&path).await?;
+    for p in &contest.problems {
+        client.recent().record_problem(&p.source, &p.id);
+    }
+    Ok(contest)
+}
+
+pub async fn run(client: &OjClient, params: GetContestParams) -> Result<CallToolResult, ErrorData> {
+    let contest = match fetch(client, &params.contest, params.source.as_deref()).await {
+        Ok(c) => c,
+        Err(e) => return e.into_tool_result(),
+    };
+
+    let mut statements = Vec::new();
+    let mut errors = Vec::new();
+    if params.include_statements.unwrap_or(false) {
+        let problems = &contest.problems;
+        let fetched: Vec<Result<Problem, FetchError>> = futures::stream::iter(0..problems.len())
+            .map(|i| problem::fetch(client, &problems[i].source, &problems[i].id))
+            .buffered(MAX_CONCURRENCY)
+            .collect()
+            .await;
+        for (summary, result) in problems.iter().zip(fetched) {
+            match result {
+                Ok(p) => statements.push(p),
+                Err(e) => errors.push(format!("{}: {}", summary.id, e.message())),
+            }
+        }
+    }
+
+    let mut md = format_contest(&contest);
+    if !errors.is_empty() {
+        md.push_str("\n## Errors\n\n");
+        for e in &errors {
+            md.push_str(&format!("- {e}\n"));
+        }
+    }
+    let sections = statements.iter().map(format_problem).collect();
+    let mut content: Vec<Content> = truncate_fair(md, sections)
+        .into_iter()
+        .map(Content::text)
+        .collect();
+    content.extend(statements.iter().map(problem_link));
+
+    let output = ContestOutput {
+        contest,
+        statements,
+        errors,
+    };
+    structured_success(&output, content)
+}
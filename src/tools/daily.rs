@@ -1,13 +1,21 @@
-use rmcp::model::{CallToolResult, Content, ErrorData};
-use rmcp::schemars;
-use serde::Deserialize;
+use std::time::Duration;
+
+use chrono::{Days, NaiveDate};
+
+use rmcp::model::{CallToolResult, Content, ErrorData, ProgressNotificationParam};
+use rmcp::service::RequestContext;
+use rmcp::{RoleServer, schemars};
+use serde::{Deserialize, Serialize};
 
 use crate::client::OjClient;
-use crate::convert::{format_problem, truncate_output};
-use crate::error::{domain_error, format_api_error, protocol_error};
+use crate::clock::Clock;
+use crate::convert::{format_problem_with, truncate_output, with_starter_code};
+use crate::error::{FetchError, format_api_error};
 use crate::models::{DailyFetching, Problem};
 
-#[derive(Deserialize, schemars::JsonSchema, Default)]
+use super::{RenderParams, structured_success};
+
+#[derive(Deserialize, schemars::JsonSchema, Default, Clone, Copy)]
 #[serde(rename_all = "lowercase")]
 pub enum Domain {
     #[default]
@@ -15,6 +23,25 @@ pub enum Domain {
     Cn,
 }
 
+impl Domain {
+    /// Timezone in which this domain's daily challenge switches at 00:00.
+    pub fn utc_offset(self) -> chrono::FixedOffset {
+        let hours = match self {
+            Self::Com => 0,
+            Self::Cn => 8,
+        };
+        chrono::FixedOffset::east_opt(hours * 3600).expect("offset is within a day")
+    }
+
+    /// Current date in this domain's timezone.
+    pub fn today(self, clock: &dyn Clock) -> NaiveDate {
+        clock.now().with_timezone(&self.utc_offset()).date_naive()
+    }
+}
+
+/// Earliest date with a daily challenge.
+pub const FIRST_DAILY: NaiveDate = NaiveDate::from_ymd_opt(2020, 4, 1).unwrap();
+
 impl std::fmt::Display for Domain {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -24,6 +51,18 @@ impl std::fmt::Display for Domain {
     }
 }
 
+impl std::str::FromStr for Domain {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "com" => Ok(Self::Com),
+            "cn" => Ok(Self::Cn),
+            _ => Err(format!("unknown domain '{s}', expected 'com' or 'cn'")),
+        }
+    }
+}
+
 #[derive(Deserialize, schemars::JsonSchema)]
 pub struct DailyParams {
     #[serde(default)]
@@ -31,57 +70,297 @@ pub struct DailyParams {
     pub domain: Option<Domain>,
 
     #[serde(default)]
-    #[schemars(description = "Date in YYYY-MM-DD format (default: today in domain's timezone: UTC+0 for 'com', UTC+8 for 'cn')")]
+    #[schemars(
+        description = "Date in YYYY-MM-DD format, or relative: 'today', 'yesterday', or '-N' for N days ago (default: today in domain's timezone: UTC+0 for 'com', UTC+8 for 'cn')"
+    )]
     pub date: Option<String>,
+
+    #[serde(default)]
+    #[schemars(
+        description = "Seconds to keep polling while the daily challenge is still being fetched (default: 0, return immediately). Capped by the server's maximum"
+    )]
+    pub wait: Option<u64>,
+
+    #[serde(default)]
+    #[schemars(
+        description = "Append the starter code for this language, e.g. 'cpp', 'python3', 'java', 'rust'"
+    )]
+    pub language: Option<String>,
+
+    #[serde(flatten)]
+    pub render: RenderParams,
 }
 
-pub async fn run(client: &OjClient, params: DailyParams) -> Result<CallToolResult, ErrorData> {
-    let domain = params.domain.unwrap_or_default();
+const DEFAULT_RETRY_SECS: u64 = 5;
 
-    let date = match params.date {
-        Some(d) => {
-            if chrono::NaiveDate::parse_from_str(&d, "%Y-%m-%d").is_err() {
-                return Ok(domain_error("invalid date format, expected YYYY-MM-DD"));
-            }
-            d
-        }
-        None => chrono::Utc::now()
-            .date_naive()
-            .format("%Y-%m-%d")
-            .to_string(),
+#[derive(Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DailyStatus {
+    Ready,
+    Fetching,
+}
+
+#[derive(Serialize, schemars::JsonSchema)]
+pub struct DailyOutput {
+    pub status: DailyStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub problem: Option<Problem>,
+}
+
+pub enum DailyOutcome {
+    Ready(Box<Problem>),
+    Fetching(Option<u64>),
+}
+
+pub fn fetching_message(retry_after: Option<u64>) -> String {
+    match retry_after {
+        Some(secs) => format!(
+            "The daily challenge is currently being fetched. Please retry after {secs} seconds."
+        ),
+        None => "The daily challenge is currently being fetched. Please retry later.".into(),
+    }
+}
+
+/// Resolves `YYYY-MM-DD`, `today`, `yesterday` or `-N` against today in the domain's
+/// timezone, rejecting dates in the future or before the first daily challenge.
+pub fn resolve_date(
+    date: Option<&str>,
+    domain: Domain,
+    clock: &dyn Clock,
+) -> Result<NaiveDate, FetchError> {
+    let today = domain.today(clock);
+    let input = date.map(str::trim).unwrap_or("today");
+
+    let days_ago = match input {
+        "today" => Some(0),
+        "yesterday" => Some(1),
+        _ => input.strip_prefix('-').and_then(|n| n.parse::<u64>().ok()),
+    };
+    let resolved = match days_ago {
+        Some(n) => today.checked_sub_days(Days::new(n)),
+        None => Some(NaiveDate::parse_from_str(input, "%Y-%m-%d").map_err(|_| {
+            FetchError::Domain(
+                "invalid date, expected YYYY-MM-DD, 'today', 'yesterday', or '-N'".into(),
+            )
+        })?),
     };
 
+    match resolved {
+        Some(d) if d > today => Err(FetchError::Domain(format!(
+            "{d} is in the future; today is {today} on the '{domain}' domain (UTC{})",
+            domain.utc_offset()
+        ))),
+        Some(d) if d >= FIRST_DAILY => Ok(d),
+        _ => Err(FetchError::Domain(format!(
+            "no daily challenges before {FIRST_DAILY} on the '{domain}' domain"
+        ))),
+    }
+}
+
+pub async fn fetch(
+    client: &OjClient,
+    domain: Domain,
+    date: Option<&str>,
+) -> Result<DailyOutcome, FetchError> {
+    let date = resolve_date(date, domain, client.clock())?;
+
     let path = format!("/api/v1/daily?domain={domain}&date={date}");
     let resp = client.get_raw(&path).await?;
 
     if resp.status == 202 {
-        let msg = if let Ok(fetching) = serde_json::from_str::<DailyFetching>(&resp.body) {
-            format!(
-                "The daily challenge is currently being fetched. Please retry after {} seconds.",
-                fetching.retry_after
-            )
-        } else {
-            "The daily challenge is currently being fetched. Please retry later.".into()
-        };
-        return Ok(CallToolResult::success(vec![Content::text(msg)]));
+        let retry_after = serde_json::from_str::<DailyFetching>(&resp.body)
+            .ok()
+            .map(|f| f.retry_after);
+        return Ok(DailyOutcome::Fetching(retry_after));
     }
 
     if resp.status != 200 {
-        return Ok(domain_error(format_api_error(resp.status, &resp.body)));
-    }
-    if !resp.is_json {
-        return Err(protocol_error("unexpected non-JSON response"));
+        return Err(FetchError::Domain(format_api_error(
+            resp.status,
+            &resp.body,
+        )));
     }
 
-    let mut problem: Problem = serde_json::from_str(&resp.body)
-        .map_err(|e| protocol_error(format!("invalid JSON: {e}")))?;
+    let mut problem: Problem = resp.json()?;
+    problem.fetched_at = resp.fetched_at;
 
     if problem.source.is_empty() {
         problem.source = "leetcode".into();
     }
+    client.recent().record_problem(&problem.source, &problem.id);
 
-    let md = format_problem(&problem);
-    Ok(CallToolResult::success(vec![Content::text(
-        truncate_output(md),
-    )]))
+    Ok(DailyOutcome::Ready(Box::new(problem)))
+}
+
+/// Polls while the backend reports 202, sleeping `retry_after` between attempts until
+/// `wait` elapses or the client cancels, and reports progress if the client asked for it.
+async fn poll(
+    client: &OjClient,
+    domain: Domain,
+    date: &str,
+    wait: Duration,
+    context: &RequestContext<RoleServer>,
+) -> Result<DailyOutcome, FetchError> {
+    let start = tokio::time::Instant::now();
+    let deadline = start + wait;
+    let progress_token = context.meta.get_progress_token();
+
+    loop {
+        let retry_after = match fetch(client, domain, Some(date)).await {
+            Ok(DailyOutcome::Fetching(retry_after)) => retry_after,
+            other => return other,
+        };
+        let now = tokio::time::Instant::now();
+        if now >= deadline {
+            return Ok(DailyOutcome::Fetching(retry_after));
+        }
+        let delay = Duration::from_secs(retry_after.unwrap_or(DEFAULT_RETRY_SECS).max(1))
+            .min(deadline - now);
+
+        if let Some(token) = &progress_token {
+            let param = ProgressNotificationParam {
+                progress_token: token.clone(),
+                progress: (now - start).as_secs_f64(),
+                total: Some(wait.as_secs_f64()),
+                message: Some(format!(
+                    "The daily challenge is being fetched, polling again in {}s",
+                    delay.as_secs()
+                )),
+            };
+            if let Err(e) = context.peer.notify_progress(param).await {
+                tracing::debug!("progress notification failed: {e}");
+            }
+        }
+
+        tokio::select! {
+            _ = context.ct.cancelled() => {
+                return Err(FetchError::Domain(
+                    "cancelled while waiting for the daily challenge".into(),
+                ));
+            }
+            _ = tokio::time::sleep(delay) => {}
+        }
+    }
+}
+
+pub async fn run(
+    client: &OjClient,
+    params: DailyParams,
+    max_wait: Duration,
+    context: RequestContext<RoleServer>,
+) -> Result<CallToolResult, ErrorData> {
+    let domain = params.domain.unwrap_or_default();
+    let date = match resolve_date(params.date.as_deref(), domain, client.clock()) {
+        Ok(d) => d.to_string(),
+        Err(e) => return e.into_tool_result(),
+    };
+    let wait = Duration::from_secs(params.wait.unwrap_or(0)).min(max_wait);
+
+    let problem = match poll(client, domain, &date, wait, &context).await {
+        Ok(DailyOutcome::Ready(p)) => *p,
+        Ok(DailyOutcome::Fetching(retry_after)) => {
+            let output = DailyOutput {
+                status: DailyStatus::Fetching,
+                retry_after,
+                problem: None,
+            };
+            return structured_success(&output, vec![Content::text(fetching_message(retry_after))]);
+        }
+        Err(e) => return e.into_tool_result(),
+    };
+
+    let md = format_problem_with(&problem, &params.render.options());
+    let md = match params.language.as_deref() {
+        Some(language) => with_starter_code(md, &problem, language),
+        None => truncate_output(md),
+    };
+    let content = params.render.content(client, &problem, md).await;
+    let output = DailyOutput {
+        status: DailyStatus::Ready,
+        retry_after: None,
+        problem: Some(problem),
+    };
+    structured_success(&output, content)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+
+    use super::*;
+
+    struct FixedClock(DateTime<Utc>);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> DateTime<Utc> {
+            self.0
+        }
+    }
+
+    fn clock(rfc3339: &str) -> FixedClock {
+        FixedClock(rfc3339.parse().expect("valid timestamp"))
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().expect("valid date")
+    }
+
+    fn resolve(input: Option<&str>, domain: Domain, clock: &FixedClock) -> Option<NaiveDate> {
+        resolve_date(input, domain, clock).ok()
+    }
+
+    #[test]
+    fn cn_rolls_over_at_utc_plus_8() {
+        let clock = clock("2024-01-01T20:00:00Z");
+        assert_eq!(Domain::Cn.today(&clock), date("2024-01-02"));
+        assert_eq!(Domain::Com.today(&clock), date("2024-01-01"));
+        assert_eq!(resolve(None, Domain::Cn, &clock), Some(date("2024-01-02")));
+        assert_eq!(resolve(None, Domain::Com, &clock), Some(date("2024-01-01")));
+    }
+
+    #[test]
+    fn relative_dates() {
+        let clock = clock("2024-03-01T12:00:00Z");
+        let com = Domain::Com;
+        assert_eq!(
+            resolve(Some("today"), com, &clock),
+            Some(date("2024-03-01"))
+        );
+        assert_eq!(
+            resolve(Some("yesterday"), com, &clock),
+            Some(date("2024-02-29"))
+        );
+        assert_eq!(resolve(Some("-7"), com, &clock), Some(date("2024-02-23")));
+        assert_eq!(resolve(Some(" -0 "), com, &clock), Some(date("2024-03-01")));
+        assert_eq!(
+            resolve(Some("2023-05-06"), com, &clock),
+            Some(date("2023-05-06"))
+        );
+        assert_eq!(resolve(Some("-x"), com, &clock), None);
+        assert_eq!(resolve(Some("2024/01/01"), com, &clock), None);
+    }
+
+    #[test]
+    fn rejects_future_dates() {
+        let clock = clock("2024-01-01T20:00:00Z");
+        assert_eq!(resolve(Some("2024-01-02"), Domain::Com, &clock), None);
+        assert_eq!(
+            resolve(Some("2024-01-02"), Domain::Cn, &clock),
+            Some(date("2024-01-02"))
+        );
+        assert_eq!(resolve(Some("2024-01-03"), Domain::Cn, &clock), None);
+    }
+
+    #[test]
+    fn rejects_dates_before_first_daily() {
+        let clock = clock("2024-01-01T00:00:00Z");
+        assert_eq!(
+            resolve(Some("2020-04-01"), Domain::Com, &clock),
+            Some(FIRST_DAILY)
+        );
+        assert_eq!(resolve(Some("2020-03-31"), Domain::Com, &clock), None);
+        assert_eq!(resolve(Some("-100000"), Domain::Cn, &clock), None);
+    }
 }
@@ -0,0 +1,257 @@
+use chrono::{Days, NaiveDate};
+use futures::StreamExt;
+use rmcp::model::{CallToolResult, Content, ErrorData};
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+
+use crate::client::OjClient;
+use crate::clock::Clock;
+use crate::convert::{format_problem, table_cell, truncate_fair};
+use crate::error::{FetchError, domain_error};
+use crate::models::Problem;
+use crate::resources::problem_link;
+
+use super::daily::{self, DailyOutcome, Domain};
+use super::structured_success;
+
+const MAX_DAYS: u64 = 31;
+const DEFAULT_DAYS: u64 = 7;
+const MAX_CONCURRENCY: usize = 4;
+
+#[derive(Deserialize, schemars::JsonSchema)]
+pub struct DailyHistoryParams {
+    #[serde(default)]
+    #[schemars(description = "LeetCode domain: 'com' (default, UTC+0) or 'cn' (UTC+8)")]
+    pub domain: Option<Domain>,
+
+    #[serde(default)]
+    #[schemars(
+        description = "First date (inclusive) in YYYY-MM-DD format, or relative: 'today', 'yesterday', or '-N' for N days ago"
+    )]
+    pub start: Option<String>,
+
+    #[serde(default)]
+    #[schemars(
+        description = "Last date (inclusive) in YYYY-MM-DD format or relative like 'start' (default: today in domain's timezone)"
+    )]
+    pub end: Option<String>,
+
+    #[serde(default)]
+    #[schemars(
+        description = "Number of days ending at 'end' to list (1-31); used instead of 'start'. Default: 7 when 'start' is omitted"
+    )]
+    pub last: Option<u64>,
+
+    #[serde(default)]
+    #[schemars(
+        description = "Dates (YYYY-MM-DD) within the range whose full problem statement should be included"
+    )]
+    pub expand: Option<Vec<String>>,
+}
+
+#[derive(Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryStatus {
+    Ready,
+    Fetching,
+    Failed,
+}
+
+#[derive(Serialize, schemars::JsonSchema)]
+pub struct DailyHistoryEntry {
+    pub date: String,
+    pub status: HistoryStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Full problem, present only for expanded dates.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub problem: Option<Problem>,
+}
+
+#[derive(Serialize, schemars::JsonSchema)]
+pub struct DailyHistoryOutput {
+    pub domain: String,
+    pub start: String,
+    pub end: String,
+    pub entries: Vec<DailyHistoryEntry>,
+}
+
+fn parse_date(name: &str, value: &str) -> Result<NaiveDate, FetchError> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map_err(|_| FetchError::Domain(format!("invalid {name} date, expected YYYY-MM-DD")))
+}
+
+fn date_range(
+    params: &DailyHistoryParams,
+    domain: Domain,
+    clock: &dyn Clock,
+) -> Result<(NaiveDate, NaiveDate), FetchError> {
+    let end = daily::resolve_date(params.end.as_deref(), domain, clock)?;
+
+    let start = match (&params.start, params.last) {
+        (Some(_), Some(_)) => {
+            return Err(FetchError::Domain(
+                "provide either 'start' or 'last', not both".into(),
+            ));
+        }
+        (Some(start), None) => daily::resolve_date(Some(start), domain, clock)?,
+        (None, last) => {
+            let days = last.unwrap_or(DEFAULT_DAYS);
+            if !(1..=MAX_DAYS).contains(&days) {
+                return Err(FetchError::Domain(format!(
+                    "last must be between 1 and {MAX_DAYS}"
+                )));
+            }
+            (end - Days::new(days - 1)).max(daily::FIRST_DAILY)
+        }
+    };
+
+    if start > end {
+        return Err(FetchError::Domain("start must not be after end".into()));
+    }
+    if (end - start).num_days() as u64 >= MAX_DAYS {
+        return Err(FetchError::Domain(format!(
+            "date range must span at most {MAX_DAYS} days"
+        )));
+    }
+    Ok((start, end))
+}
+
+fn entry(date: String, outcome: Result<DailyOutcome, FetchError>) -> DailyHistoryEntry {
+    let mut entry = DailyHistoryEntry {
+        date,
+        status: HistoryStatus::Failed,
+        id: None,
+        title: None,
+        difficulty: None,
+        tags: None,
+        retry_after: None,
+        error: None,
+        problem: None,
+    };
+    match outcome {
+        Ok(DailyOutcome::Ready(p)) => {
+            entry.status = HistoryStatus::Ready;
+            entry.id = Some(p.id.clone());
+            entry.title = Some(p.title.clone());
+            entry.difficulty = p.difficulty.clone();
+            entry.tags = p.tags.clone();
+            entry.problem = Some(*p);
+        }
+        Ok(DailyOutcome::Fetching(retry_after)) => {
+            entry.status = HistoryStatus::Fetching;
+            entry.retry_after = retry_after;
+        }
+        Err(e) => entry.error = Some(e.message()),
+    }
+    entry
+}
+
+fn format_history(domain: Domain, entries: &[DailyHistoryEntry]) -> String {
+    let mut out = format!(
+        "\
+# LeetCode Daily Challenges ({domain})
+
+| Date | ID | Title | Difficulty | Tags |
+|------|----|-------|------------|------|
+"
+    );
+    for e in entries {
+        let (id, title) = match e.status {
+            HistoryStatus::Ready => (
+                e.id.as_deref().unwrap_or("N/A"),
+                e.title.as_deref().unwrap_or("N/A"),
+            ),
+            HistoryStatus::Fetching => ("-", "(being fetched, retry later)"),
+            HistoryStatus::Failed => ("-", e.error.as_deref().unwrap_or("(failed)")),
+        };
+        let tags = match &e.tags {
+            Some(v) if !v.is_empty() => table_cell(&v.join(", ")),
+            _ => "N/A".into(),
+        };
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            e.date,
+            table_cell(id),
+            table_cell(title),
+            table_cell(e.difficulty.as_deref().unwrap_or("N/A")),
+            tags,
+        ));
+    }
+    out
+}
+
+pub async fn run(
+    client: &OjClient,
+    params: DailyHistoryParams,
+) -> Result<CallToolResult, ErrorData> {
+    let domain = params.domain.unwrap_or_default();
+    let (start, end) = match date_range(&params, domain, client.clock()) {
+        Ok(r) => r,
+        Err(e) => return e.into_tool_result(),
+    };
+    let mut expand = Vec::new();
+    for d in params.expand.iter().flatten() {
+        match parse_date("expand", d) {
+            Ok(d) if (start..=end).contains(&d) => expand.push(d),
+            Ok(d) => {
+                return Ok(domain_error(format!(
+                    "expand date {d} is outside the range"
+                )));
+            }
+            Err(e) => return e.into_tool_result(),
+        }
+    }
+
+    let dates: Vec<String> = start
+        .iter_days()
+        .take_while(|d| *d <= end)
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .collect();
+    let outcomes: Vec<Result<DailyOutcome, FetchError>> = futures::stream::iter(0..dates.len())
+        .map(|i| daily::fetch(client, domain, Some(&dates[i])))
+        .buffered(MAX_CONCURRENCY)
+        .collect()
+        .await;
+
+    let mut entries: Vec<DailyHistoryEntry> = dates
+        .into_iter()
+        .zip(outcomes)
+        .map(|(date, outcome)| entry(date, outcome))
+        .collect();
+
+    let header = format_history(domain, &entries);
+    let mut sections = Vec::new();
+    let mut links = Vec::new();
+    for (date, e) in start.iter_days().zip(&mut entries) {
+        if !expand.contains(&date) {
+            e.problem = None;
+        } else if let Some(p) = &e.problem {
+            sections.push(format_problem(p));
+            links.push(problem_link(p));
+        }
+    }
+    let mut content: Vec<Content> = truncate_fair(header, sections)
+        .into_iter()
+        .map(Content::text)
+        .collect();
+    content.extend(links);
+
+    let output = DailyHistoryOutput {
+        domain: domain.to_string(),
+        start: start.format("%Y-%m-%d").to_string(),
+        end: end.format("%Y-%m-%d").to_string(),
+        entries,
+    };
+    structured_success(&output, content)
+}
@@ -0,0 +1,228 @@
+use std::path::Path;
+use std::time::Duration;
+
+use rmcp::model::{CallToolResult, Content, ErrorData};
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+
+use crate::client::OjClient;
+use crate::convert::extract_samples;
+use crate::error::domain_error;
+use crate::judge::{self, JudgeConfig, Limits, TestResult, Verdict};
+use crate::models::IoMode;
+use crate::resources::problem_link;
+
+use super::{problem, structured_success};
+
+const DEFAULT_TIME_LIMIT_MS: u64 = 2000;
+const MAX_TIME_LIMIT_MS: u64 = 30_000;
+const DEFAULT_MEMORY_LIMIT_MB: u64 = 256;
+const MAX_MEMORY_LIMIT_MB: u64 = 4096;
+
+#[derive(Deserialize, schemars::JsonSchema)]
+pub struct RunSamplesParams {
+    #[schemars(description = "Problem source: leetcode, codeforces, atcoder, or luogu")]
+    pub source: String,
+
+    #[schemars(
+        description = "Problem ID on the platform, e.g. '1A' (codeforces), 'abc001_1' (atcoder)"
+    )]
+    pub id: String,
+
+    #[schemars(description = "Absolute path of the local source file to judge")]
+    pub file: String,
+
+    #[serde(default)]
+    #[schemars(
+        description = "Language: cpp, c, rust, go, java, python, javascript, or one from the judge config. Inferred from the file extension if omitted"
+    )]
+    pub language: Option<String>,
+
+    #[serde(default)]
+    #[schemars(
+        description = "Time limit per test in milliseconds (1-30000, default: the problem's limit, else 2000)"
+    )]
+    pub time_limit_ms: Option<u64>,
+
+    #[serde(default)]
+    #[schemars(
+        description = "Memory limit per test in megabytes (16-4096, default: the problem's limit, else 256)"
+    )]
+    pub memory_limit_mb: Option<u64>,
+}
+
+#[derive(Serialize, schemars::JsonSchema)]
+pub struct JudgeOutput {
+    pub language: String,
+    /// Compiler diagnostics when compilation failed; no tests are run then.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compile_error: Option<String>,
+    /// Memory limit applied to each test, absent when the language runs without one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_limit_mb: Option<u64>,
+    pub passed: usize,
+    pub total: usize,
+    pub results: Vec<TestResult>,
+}
+
+fn verdict_label(v: &Verdict) -> &'static str {
+    match v {
+        Verdict::Passed => "Passed",
+        Verdict::WrongAnswer => "Wrong Answer",
+        Verdict::TimeLimitExceeded => "Time Limit Exceeded",
+        Verdict::OutputLimitExceeded => "Output Limit Exceeded",
+        Verdict::RuntimeError => "Runtime Error",
+    }
+}
+
+fn format_result(r: &TestResult) -> String {
+    let mut out = format!(
+        "## Test {}: {} ({} ms)\n",
+        r.index,
+        verdict_label(&r.verdict),
+        r.time_ms
+    );
+    if let Some(diff) = &r.diff {
+        out.push_str(&format!("\n```diff\n{diff}```\n"));
+    }
+    if let Some(stderr) = &r.stderr {
+        out.push_str(&format!("\n**stderr:**\n\n```\n{stderr}\n```\n"));
+    }
+    out
+}
+
+pub async fn run(
+    client: &OjClient,
+    config: &JudgeConfig,
+    params: RunSamplesParams,
+) -> Result<CallToolResult, ErrorData> {
+    if params.source.trim().eq_ignore_ascii_case("leetcode") {
+        return Ok(domain_error(
+            "leetcode problems are solved as a function, not a program reading standard input, and cannot be judged",
+        ));
+    }
+    if params
+        .time_limit_ms
+        .is_some_and(|t| !(1..=MAX_TIME_LIMIT_MS).contains(&t))
+    {
+        return Ok(domain_error(format!(
+            "time_limit_ms must be between 1 and {MAX_TIME_LIMIT_MS}"
+        )));
+    }
+    if params
+        .memory_limit_mb
+        .is_some_and(|m| !(16..=MAX_MEMORY_LIMIT_MB).contains(&m))
+    {
+        return Ok(domain_error(format!(
+            "memory_limit_mb must be between 16 and {MAX_MEMORY_LIMIT_MB}"
+        )));
+    }
+    let file = Path::new(params.file.trim());
+    if !file.is_absolute() || !file.is_file() {
+        return Ok(domain_error(format!(
+            "'{}' is not an absolute path to an existing file",
+            file.display()
+        )));
+    }
+    let language = match config.language_for(params.language.as_deref(), file) {
+        Ok(l) => l,
+        Err(e) => return Ok(domain_error(e)),
+    };
+
+    let problem = match problem::fetch(client, &params.source, &params.id).await {
+        Ok(p) => p,
+        Err(e) => return e.into_tool_result(),
+    };
+    match problem.io_mode {
+        Some(IoMode::Interactive) => {
+            return Ok(domain_error(format!(
+                "{}/{} is interactive and cannot be judged against static samples",
+                problem.source, problem.id
+            )));
+        }
+        Some(IoMode::File) => {
+            return Ok(domain_error(format!(
+                "{}/{} reads and writes named files; only standard input/output can be judged",
+                problem.source, problem.id
+            )));
+        }
+        Some(IoMode::Stdin) | None => {}
+    }
+    // The problem's own limits are the default, clamped to what the judge allows.
+    let time_limit = params.time_limit_ms.unwrap_or_else(|| {
+        problem
+            .time_limit_ms
+            .map_or(DEFAULT_TIME_LIMIT_MS, |t| t.clamp(1, MAX_TIME_LIMIT_MS))
+    });
+    let memory_limit = params.memory_limit_mb.unwrap_or_else(|| {
+        problem
+            .memory_limit_mb
+            .map_or(DEFAULT_MEMORY_LIMIT_MB, |m| {
+                m.clamp(16, MAX_MEMORY_LIMIT_MB)
+            })
+    });
+    let samples = extract_samples(&problem);
+    if samples.is_empty() {
+        return Ok(domain_error(format!(
+            "no sample tests found in {}/{}",
+            problem.source, problem.id
+        )));
+    }
+
+    let program = match judge::prepare(config, &language, file).await {
+        Ok(p) => p,
+        Err(log) => {
+            let output = JudgeOutput {
+                language,
+                compile_error: Some(log),
+                memory_limit_mb: None,
+                passed: 0,
+                total: samples.len(),
+                results: Vec::new(),
+            };
+            let md = format!(
+                "# Compilation Error\n\n```\n{}\n```\n",
+                output.compile_error.as_deref().unwrap_or_default()
+            );
+            return structured_success(&output, vec![Content::text(md)]);
+        }
+    };
+
+    let limits = Limits {
+        time: Duration::from_millis(time_limit),
+        memory_mb: memory_limit,
+    };
+    let mut results = Vec::with_capacity(samples.len());
+    for (i, sample) in samples.iter().enumerate() {
+        results.push(program.run(i + 1, sample, &limits).await);
+    }
+
+    let passed = results
+        .iter()
+        .filter(|r| r.verdict == Verdict::Passed)
+        .count();
+    let memory_limit = program.limits_memory().then_some(memory_limit);
+    let memory = match memory_limit {
+        Some(mb) => format!("{mb} MB"),
+        None => "not applied".to_owned(),
+    };
+    let mut md = format!(
+        "# {}: {passed}/{} samples passed\n\n- Language: {language} | Time limit: {time_limit} ms | Memory limit: {memory}\n\n",
+        problem.title,
+        samples.len(),
+    );
+    for r in &results {
+        md.push_str(&format_result(r));
+        md.push('\n');
+    }
+
+    let output = JudgeOutput {
+        language,
+        compile_error: None,
+        memory_limit_mb: memory_limit,
+        passed,
+        total: samples.len(),
+        results,
+    };
+    structured_success(&output, vec![Content::text(md), problem_link(&problem)])
+}
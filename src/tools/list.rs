@@ -0,0 +1,173 @@
+use rmcp::model::{CallToolResult, Content, ErrorData};
+use rmcp::schemars;
+use serde::Deserialize;
+
+use crate::client::OjClient;
+use crate::convert::{format_problem_list, truncate_output};
+use crate::error::FetchError;
+use crate::models::ProblemListResponse;
+
+use super::structured_success;
+
+#[derive(Deserialize, schemars::JsonSchema, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum SortField {
+    Id,
+    Difficulty,
+    AcRate,
+    Title,
+}
+
+impl std::fmt::Display for SortField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Id => write!(f, "id"),
+            Self::Difficulty => write!(f, "difficulty"),
+            Self::AcRate => write!(f, "ac_rate"),
+            Self::Title => write!(f, "title"),
+        }
+    }
+}
+
+#[derive(Deserialize, schemars::JsonSchema, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+impl std::fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Asc => write!(f, "asc"),
+            Self::Desc => write!(f, "desc"),
+        }
+    }
+}
+
+#[derive(Deserialize, schemars::JsonSchema)]
+pub struct ListProblemsParams {
+    #[serde(default)]
+    #[schemars(description = "Problem source: leetcode, codeforces, atcoder, or luogu")]
+    pub source: Option<String>,
+
+    #[serde(default)]
+    #[schemars(
+        description = "Comma-separated difficulty filter as shown by the platform, e.g. 'Easy,Medium' (leetcode) or '1600' (codeforces)"
+    )]
+    pub difficulty: Option<String>,
+
+    #[serde(default)]
+    #[schemars(description = "Tags that every returned problem must have, e.g. ['dp', 'graphs']")]
+    pub tags: Option<Vec<String>>,
+
+    #[serde(default)]
+    #[schemars(description = "Minimum acceptance rate in percent (0-100)")]
+    pub min_ac_rate: Option<f64>,
+
+    #[serde(default)]
+    #[schemars(description = "Maximum acceptance rate in percent (0-100)")]
+    pub max_ac_rate: Option<f64>,
+
+    #[serde(default)]
+    #[schemars(description = "Sort field: id (default), difficulty, ac_rate, or title")]
+    pub sort: Option<SortField>,
+
+    #[serde(default)]
+    #[schemars(description = "Sort order: asc (default) or desc")]
+    pub order: Option<SortOrder>,
+
+    #[serde(default)]
+    #[schemars(description = "Cursor from a previous page's next_cursor to continue listing")]
+    pub cursor: Option<String>,
+
+    #[serde(default)]
+    #[schemars(description = "Maximum results per page (1-100, default: 20)")]
+    pub limit: Option<u32>,
+}
+
+fn check_ac_rate(name: &str, value: Option<f64>) -> Result<(), FetchError> {
+    match value {
+        Some(v) if !(0.0..=100.0).contains(&v) => Err(FetchError::Domain(format!(
+            "{name} must be between 0 and 100"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+pub async fn fetch(
+    client: &OjClient,
+    params: &ListProblemsParams,
+) -> Result<ProblemListResponse, FetchError> {
+    let limit = params.limit.unwrap_or(20);
+    if !(1..=100).contains(&limit) {
+        return Err(FetchError::Domain("limit must be between 1 and 100".into()));
+    }
+    check_ac_rate("min_ac_rate", params.min_ac_rate)?;
+    check_ac_rate("max_ac_rate", params.max_ac_rate)?;
+    if let (Some(min), Some(max)) = (params.min_ac_rate, params.max_ac_rate)
+        && min > max
+    {
+        return Err(FetchError::Domain(
+            "min_ac_rate must not be greater than max_ac_rate".into(),
+        ));
+    }
+
+    let mut qs = format!("limit={limit}");
+    let trimmed = |v: &Option<String>| {
+        v.as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_owned)
+    };
+    if let Some(source) = trimmed(&params.source) {
+        qs.push_str(&format!("&source={}", urlencoding::encode(&source)));
+    }
+    if let Some(difficulty) = trimmed(&params.difficulty) {
+        qs.push_str(&format!("&difficulty={}", urlencoding::encode(&difficulty)));
+    }
+    if let Some(tags) = &params.tags {
+        let tags: Vec<&str> = tags
+            .iter()
+            .map(|t| t.trim())
+            .filter(|t| !t.is_empty())
+            .collect();
+        if !tags.is_empty() {
+            qs.push_str(&format!("&tags={}", urlencoding::encode(&tags.join(","))));
+        }
+    }
+    if let Some(min) = params.min_ac_rate {
+        qs.push_str(&format!("&min_ac_rate={min}"));
+    }
+    if let Some(max) = params.max_ac_rate {
+        qs.push_str(&format!("&max_ac_rate={max}"));
+    }
+    if let Some(sort) = params.sort {
+        qs.push_str(&format!("&sort={sort}"));
+    }
+    if let Some(order) = params.order {
+        qs.push_str(&format!("&order={order}"));
+    }
+    if let Some(cursor) = trimmed(&params.cursor) {
+        qs.push_str(&format!("&cursor={}", urlencoding::encode(&cursor)));
+    }
+
+    let list: ProblemListResponse = client.get_json(&format!("/api/v1/problems?{qs}")).await?;
+    for p in &list.problems {
+        client.recent().record_problem(&p.source, &p.id);
+    }
+    Ok(list)
+}
+
+pub async fn run(
+    client: &OjClient,
+    params: ListProblemsParams,
+) -> Result<CallToolResult, ErrorData> {
+    let list = match fetch(client, &params).await {
+        Ok(l) => l,
+        Err(e) => return e.into_tool_result(),
+    };
+
+    let md = format_problem_list(&list);
+    structured_success(&list, vec![Content::text(truncate_output(md))])
+}
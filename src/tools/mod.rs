@@ -1,33 +1,212 @@
-mod daily;
-mod problem;
-mod resolve;
-mod similar;
-mod status;
+pub mod batch;
+pub mod contest;
+pub mod daily;
+pub mod history;
+pub mod judge;
+pub mod list;
+pub mod problem;
+pub mod random;
+pub mod resolve;
+pub mod samples;
+pub mod scaffold;
+pub mod similar;
+pub mod status;
 
-use rmcp::handler::server::tool::ToolRouter;
+use std::sync::Arc;
+use std::time::Duration;
+
+use base64::Engine;
+use futures::StreamExt;
+
+use rmcp::handler::server::router::prompt::PromptRouter;
+use rmcp::handler::server::tool::{ToolRouter, schema_for_output};
 use rmcp::handler::server::wrapper::Parameters;
 use rmcp::model::*;
-use rmcp::{ServerHandler, tool, tool_handler, tool_router};
+use rmcp::service::RequestContext;
+use rmcp::{
+    RoleServer, ServerHandler, prompt, prompt_handler, prompt_router, schemars, tool, tool_handler,
+    tool_router,
+};
 
 use crate::client::OjClient;
+use crate::convert::{MathStyle, RenderOptions, Section, StatementLang, image_urls};
+use crate::error::protocol_error;
+use crate::models::{Problem, ProblemListResponse, SimilarResponse, StatusResponse};
+use crate::resources::problem_link;
+use crate::{completion, prompts, resources};
+
+fn output_schema<T: schemars::JsonSchema + 'static>() -> Arc<JsonObject> {
+    schema_for_output::<T>().unwrap_or_else(|e| panic!("invalid output schema: {e}"))
+}
+
+fn structured_success<T: serde::Serialize>(
+    value: &T,
+    content: Vec<Content>,
+) -> Result<CallToolResult, ErrorData> {
+    let structured = serde_json::to_value(value)
+        .map_err(|e| protocol_error(format!("serialize structured content: {e}")))?;
+    let mut result = CallToolResult::success(content);
+    result.structured_content = Some(structured);
+    Ok(result)
+}
+
+const MAX_IMAGES: usize = 8;
+const MAX_IMAGE_BYTES: usize = 1 << 20;
+
+/// Hosts a problem's images may be downloaded from: its platform's sites and CDNs, and
+/// the host of its own link.
+fn image_hosts(p: &Problem) -> Vec<String> {
+    let platform: &[&str] = match p.source.to_ascii_lowercase().as_str() {
+        "leetcode" => &["leetcode.com", "leetcode.cn", "leetcode-cn.com"],
+        "codeforces" => &["codeforces.com", "codeforces.org", "codeforces.ml"],
+        "atcoder" => &["atcoder.jp"],
+        "luogu" => &["luogu.com.cn", "luogu.org"],
+        _ => &[],
+    };
+    let mut hosts: Vec<String> = platform.iter().map(|h| (*h).to_owned()).collect();
+    if let Some(host) = p
+        .link
+        .as_deref()
+        .and_then(|l| url::Url::parse(l).ok())
+        .and_then(|u| u.host_str().map(str::to_ascii_lowercase))
+    {
+        hosts.push(host);
+    }
+    hosts
+}
+
+/// Image content for the images of a rendered statement, followed by a note listing
+/// any that were skipped or could not be fetched.
+async fn statement_images(client: &OjClient, problem: &Problem, md: &str) -> Vec<Content> {
+    let images = image_urls(md);
+    let hosts = image_hosts(problem);
+    let count = images.len().min(MAX_IMAGES);
+    let fetched: Vec<_> = futures::stream::iter(0..count)
+        .map(|i| client.fetch_image(&images[i].1, &hosts, MAX_IMAGE_BYTES))
+        .buffered(4)
+        .collect()
+        .await;
+
+    let mut content = Vec::new();
+    let mut skipped = Vec::new();
+    for ((_, url), result) in images.iter().zip(fetched) {
+        match result {
+            Ok((mime, bytes)) => content.push(Content::image(
+                base64::engine::general_purpose::STANDARD.encode(bytes),
+                mime,
+            )),
+            Err(e) => skipped.push(format!("- {url}: {e}")),
+        }
+    }
+    for (_, url) in images.iter().skip(MAX_IMAGES) {
+        skipped.push(format!(
+            "- {url}: only the first {MAX_IMAGES} images are fetched"
+        ));
+    }
+    if !skipped.is_empty() {
+        content.push(Content::text(format!(
+            "Images not included:\n\n{}\n",
+            skipped.join("\n")
+        )));
+    }
+    content
+}
+
+/// Rendering parameters shared by the tools that return a full statement.
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct RenderParams {
+    #[serde(default)]
+    #[schemars(
+        description = "Formula style: 'latex' (default) writes $...$ and $$...$$; 'unicode' renders simple expressions as plain text, e.g. 10⁵ and ≤, for clients without math rendering"
+    )]
+    pub math: Option<MathStyle>,
+
+    #[serde(default)]
+    #[schemars(
+        description = "Only include these statement sections: description, input, output, constraints, examples, notes, hints (default: the full statement)"
+    )]
+    pub sections: Option<Vec<Section>>,
+
+    #[serde(default)]
+    #[schemars(
+        description = "Statement language when several exist: 'en' (default), 'ja' (AtCoder), or 'zh' (LeetCode CN translation). Falls back to the available one"
+    )]
+    pub lang: Option<StatementLang>,
+
+    #[serde(default)]
+    #[schemars(
+        description = "Also return the statement's images as image content (PNG, JPEG, GIF or WebP, up to 1 MiB each, at most 8) for clients that can view them (default: false)"
+    )]
+    pub images: Option<bool>,
+
+    #[serde(default)]
+    #[schemars(
+        description = "Replace links with their text for more compact output; images are kept (default: false)"
+    )]
+    pub strip_links: Option<bool>,
+}
+
+impl RenderParams {
+    pub fn options(&self) -> RenderOptions {
+        RenderOptions {
+            math: self.math.unwrap_or_default(),
+            sections: self.sections.clone().unwrap_or_default(),
+            lang: self.lang,
+            strip_links: self.strip_links.unwrap_or(false),
+        }
+    }
+
+    /// Content for a rendered statement: the markdown, the problem link and, when
+    /// requested, the statement's images.
+    async fn content(&self, client: &OjClient, problem: &Problem, md: String) -> Vec<Content> {
+        let images = if self.images.unwrap_or(false) {
+            statement_images(client, problem, &md).await
+        } else {
+            Vec::new()
+        };
+        let mut content = vec![Content::text(md), problem_link(problem)];
+        content.extend(images);
+        content
+    }
+}
+
+pub struct ServerConfig {
+    pub daily_max_wait: Duration,
+    /// Judge commands, or `None` when run_samples is not enabled.
+    pub judge: Option<crate::judge::JudgeConfig>,
+    /// Scaffolding settings, or `None` when scaffold_problem is not available.
+    pub scaffold: Option<scaffold::ScaffoldConfig>,
+}
 
 #[derive(Clone)]
 pub struct OjServer {
     client: OjClient,
+    config: Arc<ServerConfig>,
     tool_router: ToolRouter<Self>,
+    prompt_router: PromptRouter<Self>,
 }
 
 #[tool_router]
 impl OjServer {
-    pub fn new(client: OjClient) -> Self {
+    pub fn new(client: OjClient, config: ServerConfig) -> Self {
+        let mut tool_router = Self::tool_router();
+        if config.judge.is_none() {
+            tool_router.remove_route("run_samples");
+        }
+        if config.scaffold.is_none() {
+            tool_router.remove_route("scaffold_problem");
+        }
         Self {
             client,
-            tool_router: Self::tool_router(),
+            config: Arc::new(config),
+            tool_router,
+            prompt_router: Self::prompt_router(),
         }
     }
 
     #[tool(
-        description = "Preferred way to look up a problem. Accepts a URL, problem slug, or prefixed ID and returns the full problem (title, difficulty, tags, and description). Supports LeetCode, Codeforces, AtCoder, and Luogu. Use this when the input format is uncertain; use get_problem when source and ID are already known."
+        description = "Preferred way to look up a problem. Accepts a URL, problem slug, or prefixed ID and returns the full problem (title, difficulty, tags, and description). Supports LeetCode, Codeforces, AtCoder, and Luogu. Use this when the input format is uncertain; use get_problem when source and ID are already known.",
+        output_schema = output_schema::<Problem>()
     )]
     async fn resolve_problem(
         &self,
@@ -37,14 +216,16 @@ impl OjServer {
     }
 
     #[tool(
-        description = "Get problem counts and indexing coverage for each platform (LeetCode, Codeforces, AtCoder, Luogu). Returns total problems, missing content count, and un-embedded count per platform."
+        description = "Get problem counts and indexing coverage for each platform (LeetCode, Codeforces, AtCoder, Luogu). Returns total problems, missing content count, and un-embedded count per platform.",
+        output_schema = output_schema::<StatusResponse>()
     )]
     async fn get_platform_status(&self) -> Result<CallToolResult, ErrorData> {
         status::run(&self.client).await
     }
 
     #[tool(
-        description = "Get a specific problem by source and ID. Returns the full problem including title, difficulty, tags, and description. Supports LeetCode, Codeforces, AtCoder, and Luogu. Use resolve_problem instead when the input is a URL or the ID format is uncertain."
+        description = "Get a specific problem by source and ID. Returns the full problem including title, difficulty, tags, and description. Supports LeetCode, Codeforces, AtCoder, and Luogu. Use resolve_problem instead when the input is a URL or the ID format is uncertain.",
+        output_schema = output_schema::<Problem>()
     )]
     async fn get_problem(
         &self,
@@ -54,17 +235,119 @@ impl OjServer {
     }
 
     #[tool(
-        description = "Get the LeetCode daily challenge problem. Returns the full problem including title, difficulty, tags, and description. Defaults to today in domain's timezone (UTC+0 for 'com', UTC+8 for 'cn'). Daily challenge switches at 00:00 in respective timezone."
+        description = "Extract a problem's sample tests as structured input, output, and explanation records, one content block per sample. Understands LeetCode examples, Codeforces sample-test blocks, AtCoder sample sections, and markdown statements such as Luogu's.",
+        output_schema = output_schema::<samples::SamplesOutput>()
+    )]
+    async fn get_samples(
+        &self,
+        params: Parameters<samples::GetSamplesParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        samples::run(&self.client, params.0).await
+    }
+
+    #[tool(
+        description = "Compile and run a local source file against a problem's sample tests and report pass/fail per test with diffs. Applies per-test time limits and, where supported, memory limits. Languages: cpp, c, rust, go, java, python, javascript, plus any from the server's judge config.",
+        output_schema = output_schema::<judge::JudgeOutput>()
+    )]
+    async fn run_samples(
+        &self,
+        params: Parameters<judge::RunSamplesParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        match &self.config.judge {
+            Some(judge) => judge::run(&self.client, judge, params.0).await,
+            None => Ok(crate::error::domain_error(
+                "run_samples is not enabled on this server",
+            )),
+        }
+    }
+
+    #[tool(
+        description = "Create a working directory for a problem: README.md with the statement, samples/N.in and samples/N.out, and a solution stub for the chosen language. Writes only under the server's workspace root or the client's declared roots and keeps existing files unless overwrite is set.",
+        output_schema = output_schema::<scaffold::ScaffoldOutput>()
+    )]
+    async fn scaffold_problem(
+        &self,
+        params: Parameters<scaffold::ScaffoldParams>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        match &self.config.scaffold {
+            Some(scaffold) => scaffold::run(&self.client, scaffold, params.0, context).await,
+            None => Ok(crate::error::domain_error(
+                "scaffold_problem is not enabled on this server",
+            )),
+        }
+    }
+
+    #[tool(
+        description = "Get several problems in one call, e.g. a whole contest. Each item is either a source + ID pair or a resolve query (URL, slug, or prefixed ID). Items are fetched concurrently; failures are listed per item instead of failing the whole call.",
+        output_schema = output_schema::<batch::BatchOutput>()
+    )]
+    async fn get_problems(
+        &self,
+        params: Parameters<batch::GetProblemsParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        batch::run(&self.client, params.0).await
+    }
+
+    #[tool(
+        description = "Get a whole contest's problem list in order, with titles, difficulty, and links. Accepts a Codeforces round number, an AtCoder contest ID such as 'abc300', or a contest URL. Set include_statements to also fetch every full statement.",
+        output_schema = output_schema::<contest::ContestOutput>()
+    )]
+    async fn get_contest(
+        &self,
+        params: Parameters<contest::GetContestParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        contest::run(&self.client, params.0).await
+    }
+
+    #[tool(
+        description = "Browse problems with filters instead of looking one up. Filter by source, difficulty, tags, and acceptance-rate range, sort by ID, difficulty, acceptance rate, or title, and page through results with the returned cursor. Returns a summary table; use get_problem for full statements.",
+        output_schema = output_schema::<ProblemListResponse>()
+    )]
+    async fn list_problems(
+        &self,
+        params: Parameters<list::ListProblemsParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        list::run(&self.client, params.0).await
+    }
+
+    #[tool(
+        description = "Pick a random problem for practice, optionally restricted to a source, difficulty labels or rating band, and tags, skipping given IDs. Pass the returned seed back to reproduce the same pick. Works from the local cache in offline mode.",
+        output_schema = output_schema::<random::RandomOutput>()
+    )]
+    async fn random_problem(
+        &self,
+        params: Parameters<random::RandomProblemParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        random::run(&self.client, params.0).await
+    }
+
+    #[tool(
+        description = "Get the LeetCode daily challenge problem. Returns the full problem including title, difficulty, tags, and description. Defaults to today in domain's timezone (UTC+0 for 'com', UTC+8 for 'cn'). Daily challenge switches at 00:00 in respective timezone. Set 'wait' to keep polling while the backend is still fetching the problem.",
+        output_schema = output_schema::<daily::DailyOutput>()
     )]
     async fn get_daily_challenge(
         &self,
         params: Parameters<daily::DailyParams>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, ErrorData> {
-        daily::run(&self.client, params.0).await
+        daily::run(&self.client, params.0, self.config.daily_max_wait, context).await
     }
 
     #[tool(
-        description = "Find similar problems by problem ID or free-text query across LeetCode, Codeforces, AtCoder, and Luogu. Returns a ranked list with similarity scores. Provide either a text query, or a source + ID pair."
+        description = "List past LeetCode daily challenges over a date range (start/end or the last N days, at most 31) as a compact table of date, ID, title, difficulty, and tags. Dates still being fetched are marked instead of failing the call. List dates in 'expand' to include their full statements.",
+        output_schema = output_schema::<history::DailyHistoryOutput>()
+    )]
+    async fn get_daily_history(
+        &self,
+        params: Parameters<history::DailyHistoryParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        history::run(&self.client, params.0).await
+    }
+
+    #[tool(
+        description = "Find similar problems by problem ID or free-text query across LeetCode, Codeforces, AtCoder, and Luogu. Returns a ranked list with similarity scores. Provide either a text query, or a source + ID pair.",
+        output_schema = output_schema::<SimilarResponse>()
     )]
     async fn find_similar_problems(
         &self,
@@ -74,7 +357,51 @@ impl OjServer {
     }
 }
 
+#[prompt_router]
+impl OjServer {
+    #[prompt(
+        description = "Solve a problem: fetches the statement and asks for observations, the algorithm with its complexity, and a complete solution in the given language."
+    )]
+    async fn solve_problem(
+        &self,
+        params: Parameters<prompts::SolveParams>,
+    ) -> Result<GetPromptResult, ErrorData> {
+        prompts::solve_problem(&self.client, params.0).await
+    }
+
+    #[prompt(
+        description = "Explain the intended approach to a problem step by step, without writing the full solution."
+    )]
+    async fn explain_approach(
+        &self,
+        params: Parameters<prompts::ExplainParams>,
+    ) -> Result<GetPromptResult, ErrorData> {
+        prompts::explain_approach(&self.client, params.0).await
+    }
+
+    #[prompt(
+        description = "Review a solution against the problem statement: correctness, missed edge cases, complexity, and improvements."
+    )]
+    async fn review_my_solution(
+        &self,
+        params: Parameters<prompts::ReviewParams>,
+    ) -> Result<GetPromptResult, ErrorData> {
+        prompts::review_my_solution(&self.client, params.0).await
+    }
+
+    #[prompt(
+        description = "Practice today's LeetCode daily challenge with hints first and the full solution on request."
+    )]
+    async fn daily_practice(
+        &self,
+        params: Parameters<prompts::DailyPracticeParams>,
+    ) -> Result<GetPromptResult, ErrorData> {
+        prompts::daily_practice(&self.client, params.0).await
+    }
+}
+
 #[tool_handler]
+#[prompt_handler]
 impl ServerHandler for OjServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
@@ -82,6 +409,14 @@ impl ServerHandler for OjServer {
                 tools: Some(ToolsCapability {
                     list_changed: Some(false),
                 }),
+                completions: Some(JsonObject::new()),
+                prompts: Some(PromptsCapability {
+                    list_changed: Some(false),
+                }),
+                resources: Some(ResourcesCapability {
+                    subscribe: Some(false),
+                    list_changed: Some(false),
+                }),
                 ..Default::default()
             },
             server_info: Implementation {
@@ -92,4 +427,38 @@ impl ServerHandler for OjServer {
             ..Default::default()
         }
     }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, ErrorData> {
+        Ok(ListResourcesResult::with_all_items(resources::list()))
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, ErrorData> {
+        Ok(ListResourceTemplatesResult::with_all_items(
+            resources::templates(),
+        ))
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, ErrorData> {
+        resources::read(&self.client, &request.uri).await
+    }
+
+    async fn complete(
+        &self,
+        request: CompleteRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<CompleteResult, ErrorData> {
+        Ok(completion::complete(&self.client, &request).await)
+    }
 }
@@ -1,12 +1,14 @@
-use rmcp::model::{CallToolResult, Content, ErrorData};
+use rmcp::model::{CallToolResult, ErrorData};
 use rmcp::schemars;
 use serde::Deserialize;
 
 use crate::client::OjClient;
-use crate::convert::{format_problem, truncate_output};
-use crate::error::{domain_error, format_api_error, protocol_error};
+use crate::convert::{format_problem_with, truncate_output, with_starter_code};
+use crate::error::FetchError;
 use crate::models::Problem;
 
+use super::{RenderParams, structured_success};
+
 #[derive(Deserialize, schemars::JsonSchema)]
 pub struct GetProblemParams {
     #[schemars(description = "Problem source: leetcode, codeforces, atcoder, or luogu")]
@@ -15,32 +17,44 @@ pub struct GetProblemParams {
         description = "Problem ID on the platform. Examples: '1' or 'two-sum' (leetcode), '1A' (codeforces), 'abc001_1' (atcoder), 'P1001' (luogu)"
     )]
     pub id: String,
+
+    #[serde(default)]
+    #[schemars(
+        description = "Append the starter code for this language, e.g. 'cpp', 'python3', 'java', 'rust' (LeetCode only)"
+    )]
+    pub language: Option<String>,
+
+    #[serde(flatten)]
+    pub render: RenderParams,
 }
 
-pub async fn run(client: &OjClient, params: GetProblemParams) -> Result<CallToolResult, ErrorData> {
-    let source = params.source.trim();
-    let id = params.id.trim();
+pub async fn fetch(client: &OjClient, source: &str, id: &str) -> Result<Problem, FetchError> {
+    let source = source.trim();
+    let id = id.trim();
     if source.is_empty() || id.is_empty() {
-        return Ok(domain_error("source and id must be non-empty"));
+        return Err(FetchError::Domain("source and id must be non-empty".into()));
     }
 
     let encoded_source = urlencoding::encode(source);
     let encoded_id = urlencoding::encode(id);
     let path = format!("/api/v1/problems/{encoded_source}/{encoded_id}");
-    let resp = client.get_raw(&path).await?;
-
-    if resp.status != 200 {
-        return Ok(domain_error(format_api_error(resp.status, &resp.body)));
-    }
-    if !resp.is_json {
-        return Err(protocol_error("unexpected non-JSON response"));
-    }
+    let (mut problem, fetched_at): (Problem, _) = client.get_json_dated(&path).await?;
+    problem.fetched_at = fetched_at;
+    client.recent().record_problem(&problem.source, &problem.id);
+    Ok(problem)
+}
 
-    let problem: Problem = serde_json::from_str(&resp.body)
-        .map_err(|e| protocol_error(format!("invalid JSON: {e}")))?;
+pub async fn run(client: &OjClient, params: GetProblemParams) -> Result<CallToolResult, ErrorData> {
+    let problem = match fetch(client, &params.source, &params.id).await {
+        Ok(p) => p,
+        Err(e) => return e.into_tool_result(),
+    };
 
-    let md = format_problem(&problem);
-    Ok(CallToolResult::success(vec![Content::text(
-        truncate_output(md),
-    )]))
+    let md = format_problem_with(&problem, &params.render.options());
+    let md = match params.language.as_deref() {
+        Some(language) => with_starter_code(md, &problem, language),
+        None => truncate_output(md),
+    };
+    let content = params.render.content(client, &problem, md).await;
+    structured_success(&problem, content)
 }
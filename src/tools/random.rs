@@ -0,0 +1,425 @@
+use rmcp::model::{CallToolResult, Content, ErrorData};
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+
+use crate::client::OjClient;
+use crate::convert::{format_problem, truncate_output};
+use crate::error::FetchError;
+use crate::models::{Problem, ProblemListResponse};
+use crate::resources::problem_link;
+
+use super::list::{self, ListProblemsParams, SortField, SortOrder};
+use super::{problem, structured_success};
+
+const PAGE_SIZE: u32 = 100;
+const MAX_PAGES: usize = 10;
+
+#[derive(Deserialize, schemars::JsonSchema)]
+pub struct RandomProblemParams {
+    #[serde(default)]
+    #[schemars(description = "Problem source: leetcode, codeforces, atcoder, or luogu")]
+    pub source: Option<String>,
+
+    #[serde(default)]
+    #[schemars(
+        description = "Comma-separated difficulty labels (e.g. 'Easy,Medium') or a numeric rating band (e.g. '1200-1600')"
+    )]
+    pub difficulty: Option<String>,
+
+    #[serde(default)]
+    #[schemars(description = "Tags that the picked problem must all have, e.g. ['dp', 'graphs']")]
+    pub tags: Option<Vec<String>>,
+
+    #[serde(default)]
+    #[schemars(
+        description = "Problem IDs to skip, either bare ('1A') or with source ('codeforces/1A')"
+    )]
+    pub exclude: Option<Vec<String>>,
+
+    #[serde(default)]
+    #[schemars(
+        description = "Seed for a reproducible pick; the same seed and filters give the same problem. Random if omitted"
+    )]
+    pub seed: Option<u64>,
+}
+
+#[derive(Serialize, schemars::JsonSchema)]
+pub struct RandomOutput {
+    /// Seed that reproduces this pick.
+    pub seed: u64,
+    /// Number of matching problems the pick was drawn from.
+    pub candidates: usize,
+    pub problem: Problem,
+}
+
+enum DifficultyFilter {
+    Labels(Vec<String>),
+    Band(u32, u32),
+}
+
+impl DifficultyFilter {
+    fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if s.is_empty() {
+            return None;
+        }
+        if let Some((lo, hi)) = s.split_once('-')
+            && let (Ok(lo), Ok(hi)) = (lo.trim().parse(), hi.trim().parse())
+        {
+            return Some(Self::Band(lo, hi));
+        }
+        let labels = s
+            .split(',')
+            .map(|l| l.trim().to_lowercase())
+            .filter(|l| !l.is_empty())
+            .collect();
+        Some(Self::Labels(labels))
+    }
+
+    fn matches(&self, difficulty: Option<&str>) -> bool {
+        let Some(d) = difficulty.map(str::trim) else {
+            return false;
+        };
+        match self {
+            Self::Labels(labels) => labels.iter().any(|l| l.eq_ignore_ascii_case(d)),
+            Self::Band(lo, hi) => d.parse::<u32>().is_ok_and(|r| (*lo..=*hi).contains(&r)),
+        }
+    }
+}
+
+struct Filter {
+    source: Option<String>,
+    difficulty: Option<DifficultyFilter>,
+    tags: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl Filter {
+    fn new(params: &RandomProblemParams) -> Result<Self, FetchError> {
+        let difficulty = params
+            .difficulty
+            .as_deref()
+            .and_then(DifficultyFilter::parse);
+        if let Some(DifficultyFilter::Band(lo, hi)) = difficulty
+            && lo > hi
+        {
+            return Err(FetchError::Domain(format!(
+                "invalid difficulty band {lo}-{hi}, lower bound is above upper bound"
+            )));
+        }
+        let normalize = |v: &Option<Vec<String>>| -> Vec<String> {
+            v.iter()
+                .flatten()
+                .map(|s| s.trim().to_lowercase())
+                .filter(|s| !s.is_empty())
+                .collect()
+        };
+        Ok(Self {
+            source: params
+                .source
+                .as_deref()
+                .map(|s| s.trim().to_lowercase())
+                .filter(|s| !s.is_empty()),
+            difficulty,
+            tags: normalize(&params.tags),
+            exclude: normalize(&params.exclude),
+        })
+    }
+
+    fn matches(
+        &self,
+        source: &str,
+        id: &str,
+        difficulty: Option<&str>,
+        tags: Option<&[String]>,
+    ) -> bool {
+        if self
+            .source
+            .as_deref()
+            .is_some_and(|s| !s.eq_ignore_ascii_case(source))
+        {
+            return false;
+        }
+        if self
+            .difficulty
+            .as_ref()
+            .is_some_and(|d| !d.matches(difficulty))
+        {
+            return false;
+        }
+        let tags = tags.unwrap_or_default();
+        if !self
+            .tags
+            .iter()
+            .all(|want| tags.iter().any(|t| t.eq_ignore_ascii_case(want)))
+        {
+            return false;
+        }
+        let id = id.to_lowercase();
+        let qualified = format!("{}/{id}", source.to_lowercase());
+        !self.exclude.iter().any(|e| *e == id || *e == qualified)
+    }
+}
+
+/// SplitMix64 finalizer, so a seed maps to the same pick across builds and platforms.
+fn mix(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Orderings the backend list can be walked in. When more problems match than
+/// [`MAX_PAGES`] pages hold, the seed picks one, so different seeds draw from
+/// different slices of the catalogue rather than always the lowest IDs.
+const ORDERINGS: [(SortField, SortOrder); 8] = [
+    (SortField::Id, SortOrder::Asc),
+    (SortField::Id, SortOrder::Desc),
+    (SortField::Difficulty, SortOrder::Asc),
+    (SortField::Difficulty, SortOrder::Desc),
+    (SortField::AcRate, SortOrder::Asc),
+    (SortField::AcRate, SortOrder::Desc),
+    (SortField::Title, SortOrder::Asc),
+    (SortField::Title, SortOrder::Desc),
+];
+
+/// Ordering chosen by `seed`, independent of the index [`pick`] derives from it.
+fn ordering(seed: u64) -> (SortField, SortOrder) {
+    ORDERINGS[(mix(!seed) % ORDERINGS.len() as u64) as usize]
+}
+
+/// Matching `(source, id)` pairs from successive pages returned by `next_page`, and
+/// whether the walk was cut short at [`MAX_PAGES`].
+async fn walk_pages<F, Fut>(
+    filter: &Filter,
+    mut next_page: F,
+) -> Result<(Vec<(String, String)>, bool), FetchError>
+where
+    F: FnMut(Option<String>) -> Fut,
+    Fut: Future<Output = Result<ProblemListResponse, FetchError>>,
+{
+    let mut found = Vec::new();
+    let mut cursor = None;
+    for _ in 0..MAX_PAGES {
+        let page = next_page(cursor).await?;
+        found.extend(
+            page.problems
+                .into_iter()
+                .filter(|p| {
+                    filter.matches(&p.source, &p.id, p.difficulty.as_deref(), p.tags.as_deref())
+                })
+                .map(|p| (p.source, p.id)),
+        );
+        match page.next_cursor {
+            Some(next) => cursor = Some(next),
+            None => return Ok((found, false)),
+        }
+    }
+    Ok((found, true))
+}
+
+/// Matching `(source, id)` pairs from the backend problem list, walked in the
+/// ordering chosen by `seed`, and whether the list was cut short at [`MAX_PAGES`].
+async fn online_candidates(
+    client: &OjClient,
+    params: &RandomProblemParams,
+    filter: &Filter,
+    seed: u64,
+) -> Result<(Vec<(String, String)>, bool), FetchError> {
+    // A rating band cannot be expressed as a list filter, so it is applied locally.
+    let difficulty = match filter.difficulty {
+        Some(DifficultyFilter::Labels(_)) => params.difficulty.clone(),
+        _ => None,
+    };
+    let (sort, order) = ordering(seed);
+    walk_pages(filter, |cursor| {
+        let query = ListProblemsParams {
+            source: filter.source.clone(),
+            difficulty: difficulty.clone(),
+            tags: params.tags.clone(),
+            min_ac_rate: None,
+            max_ac_rate: None,
+            sort: Some(sort),
+            order: Some(order),
+            cursor,
+            limit: Some(PAGE_SIZE),
+        };
+        async move { list::fetch(client, &query).await }
+    })
+    .await
+}
+
+/// Matching problems kept in the on-disk store. These are picked from directly, since
+/// a problem stored under its slug cannot be reloaded by its ID.
+async fn offline_candidates(client: &OjClient, filter: &Filter) -> Vec<Problem> {
+    client
+        .stored("/api/v1/problems/")
+        .await
+        .iter()
+        .filter_map(|resp| {
+            let mut problem = resp.json::<Problem>().ok()?;
+            problem.fetched_at = resp.fetched_at;
+            Some(problem)
+        })
+        .filter(|p| filter.matches(&p.source, &p.id, p.difficulty.as_deref(), p.tags.as_deref()))
+        .collect()
+}
+
+/// Index chosen by `seed` among `len` candidates.
+fn pick(seed: u64, len: usize) -> usize {
+    (mix(seed) % len as u64) as usize
+}
+
+pub async fn fetch(
+    client: &OjClient,
+    params: &RandomProblemParams,
+) -> Result<(RandomOutput, bool), FetchError> {
+    let filter = Filter::new(params)?;
+    // Generated seeds stay below 2^53 so clients that read JSON numbers as f64 echo
+    // them back exactly.
+    let seed = params.seed.unwrap_or_else(|| rand::random::<u64>() >> 11);
+
+    // Candidates are sorted so a seed does not depend on backend page order or
+    // directory listing order.
+    let (problem, candidates, capped) = if client.is_offline() {
+        let mut found = offline_candidates(client, &filter).await;
+        found.sort_by(|a, b| (&a.source, &a.id).cmp(&(&b.source, &b.id)));
+        found.dedup_by(|a, b| a.source == b.source && a.id == b.id);
+        if found.is_empty() {
+            return Err(FetchError::Domain(
+                "no cached problems match the given filters".into(),
+            ));
+        }
+        let len = found.len();
+        let problem = found.swap_remove(pick(seed, len));
+        client.recent().record_problem(&problem.source, &problem.id);
+        (problem, len, false)
+    } else {
+        let (mut found, capped) = online_candidates(client, params, &filter, seed).await?;
+        found.sort();
+        found.dedup();
+        if found.is_empty() {
+            return Err(FetchError::Domain(
+                "no problems match the given filters".into(),
+            ));
+        }
+        let (source, id) = &found[pick(seed, found.len())];
+        let problem = problem::fetch(client, source, id).await?;
+        (problem, found.len(), capped)
+    };
+
+    let output = RandomOutput {
+        seed,
+        candidates,
+        problem,
+    };
+    Ok((output, capped))
+}
+
+pub async fn run(
+    client: &OjClient,
+    params: RandomProblemParams,
+) -> Result<CallToolResult, ErrorData> {
+    let (output, capped) = match fetch(client, &params).await {
+        Ok(r) => r,
+        Err(e) => return e.into_tool_result(),
+    };
+
+    let scope = if capped { "a sample of " } else { "" };
+    let md = format!(
+        "Picked from {scope}{} matching problems (seed: {}).\n\n{}",
+        output.candidates,
+        output.seed,
+        format_problem(&output.problem)
+    );
+    let content = vec![
+        Content::text(truncate_output(md)),
+        problem_link(&output.problem),
+    ];
+    structured_success(&output, content)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::ProblemSummary;
+
+    use super::*;
+
+    const CATALOGUE: u64 = 5000;
+
+    /// A backend holding problems `1..=CATALOGUE`, where each sort field orders them
+    /// by a different permutation of their IDs.
+    fn page(sort: SortField, order: SortOrder, cursor: Option<String>) -> ProblemListResponse {
+        let key = |n: u64| match sort {
+            SortField::Id => n,
+            SortField::Difficulty => n * 37 % CATALOGUE,
+            SortField::AcRate => n * 101 % CATALOGUE,
+            SortField::Title => n * 7 % CATALOGUE,
+        };
+        let mut ids: Vec<u64> = (1..=CATALOGUE).collect();
+        ids.sort_by_key(|&n| key(n));
+        if let SortOrder::Desc = order {
+            ids.reverse();
+        }
+        let start: usize = cursor.map_or(0, |c| c.parse().expect("numeric cursor"));
+        let end = (start + PAGE_SIZE as usize).min(ids.len());
+        ProblemListResponse {
+            problems: ids[start..end]
+                .iter()
+                .map(|n| ProblemSummary {
+                    source: "codeforces".into(),
+                    id: n.to_string(),
+                    title: format!("Problem {n}"),
+                    difficulty: None,
+                    ac_rate: None,
+                    tags: None,
+                    link: None,
+                })
+                .collect(),
+            next_cursor: (end < ids.len()).then(|| end.to_string()),
+            total: Some(CATALOGUE),
+        }
+    }
+
+    fn picked_id(seed: u64) -> u64 {
+        let params = RandomProblemParams {
+            source: None,
+            difficulty: None,
+            tags: None,
+            exclude: None,
+            seed: Some(seed),
+        };
+        let Ok(filter) = Filter::new(&params) else {
+            panic!("invalid filter");
+        };
+        let (sort, order) = ordering(seed);
+        let walk = walk_pages(
+            &filter,
+            |cursor| async move { Ok(page(sort, order, cursor)) },
+        );
+        let Ok((mut found, capped)) = futures::executor::block_on(walk) else {
+            panic!("page walk failed");
+        };
+        assert!(capped);
+        found.sort();
+        found[pick(seed, found.len())]
+            .1
+            .parse()
+            .expect("numeric id")
+    }
+
+    #[test]
+    fn picks_reach_past_the_first_pages() {
+        let window = u64::from(PAGE_SIZE) * MAX_PAGES as u64;
+        let picks: Vec<u64> = (0..64).map(picked_id).collect();
+        assert!(picks.iter().any(|&id| id > window), "{picks:?}");
+        assert!(picks.iter().any(|&id| id > CATALOGUE - window), "{picks:?}");
+    }
+
+    #[test]
+    fn seed_reproduces_the_pick() {
+        for seed in 0..16 {
+            assert_eq!(picked_id(seed), picked_id(seed));
+        }
+    }
+}
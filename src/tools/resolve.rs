@@ -3,9 +3,11 @@ use rmcp::schemars;
 use serde::Deserialize;
 
 use crate::client::OjClient;
-use crate::convert::{format_problem, truncate_output};
-use crate::error::{domain_error, format_api_error, protocol_error};
-use crate::models::ResolveResponse;
+use crate::convert::{format_problem_with, truncate_output};
+use crate::error::FetchError;
+use crate::models::{Problem, ResolveResponse};
+
+use super::{RenderParams, structured_success};
 
 #[derive(Deserialize, schemars::JsonSchema)]
 pub struct ResolveParams {
@@ -13,25 +15,28 @@ pub struct ResolveParams {
         description = "A problem URL, slug, or prefixed ID. Examples: 'https://leetcode.com/problems/two-sum', 'https://codeforces.com/problemset/problem/1/A', 'leetcode/two-sum', 'cf1A', 'P1001'"
     )]
     pub query: String,
+
+    #[serde(flatten)]
+    pub render: RenderParams,
 }
 
-pub async fn run(client: &OjClient, params: ResolveParams) -> Result<CallToolResult, ErrorData> {
-    let encoded = urlencoding::encode(&params.query);
+pub async fn fetch(client: &OjClient, query: &str) -> Result<Problem, FetchError> {
+    let encoded = urlencoding::encode(query);
     let path = format!("/api/v1/resolve/{encoded}");
-    let resp = client.get_raw(&path).await?;
-
-    if resp.status != 200 {
-        return Ok(domain_error(format_api_error(resp.status, &resp.body)));
-    }
-    if !resp.is_json {
-        return Err(protocol_error("unexpected non-JSON response"));
-    }
+    let (parsed, fetched_at): (ResolveResponse, _) = client.get_json_dated(&path).await?;
+    let mut problem = parsed.problem;
+    problem.fetched_at = fetched_at;
+    client.recent().record_problem(&problem.source, &problem.id);
+    Ok(problem)
+}
 
-    let parsed: ResolveResponse = serde_json::from_str(&resp.body)
-        .map_err(|e| protocol_error(format!("invalid JSON: {e}")))?;
+pub async fn run(client: &OjClient, params: ResolveParams) -> Result<CallToolResult, ErrorData> {
+    let problem = match fetch(client, &params.query).await {
+        Ok(p) => p,
+        Err(e) => return e.into_tool_result(),
+    };
 
-    let md = format_problem(&parsed.problem);
-    Ok(CallToolResult::success(vec![rmcp::model::Content::text(
-        truncate_output(md),
-    )]))
+    let md = truncate_output(format_problem_with(&problem, &params.render.options()));
+    let content = params.render.content(client, &problem, md).await;
+    structured_success(&problem, content)
 }
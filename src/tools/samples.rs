@@ -0,0 +1,61 @@
+use rmcp::model::{CallToolResult, Content, ErrorData};
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+
+use crate::client::OjClient;
+use crate::convert::{extract_samples, format_sample};
+use crate::models::Sample;
+use crate::resources::problem_link;
+
+use super::{problem, structured_success};
+
+#[derive(Deserialize, schemars::JsonSchema)]
+pub struct GetSamplesParams {
+    #[schemars(description = "Problem source: leetcode, codeforces, atcoder, or luogu")]
+    pub source: String,
+    #[schemars(
+        description = "Problem ID on the platform. Examples: '1' or 'two-sum' (leetcode), '1A' (codeforces), 'abc001_1' (atcoder), 'P1001' (luogu)"
+    )]
+    pub id: String,
+}
+
+#[derive(Serialize, schemars::JsonSchema)]
+pub struct SamplesOutput {
+    pub source: String,
+    pub id: String,
+    pub title: String,
+    pub samples: Vec<Sample>,
+}
+
+pub async fn run(client: &OjClient, params: GetSamplesParams) -> Result<CallToolResult, ErrorData> {
+    let problem = match problem::fetch(client, &params.source, &params.id).await {
+        Ok(p) => p,
+        Err(e) => return e.into_tool_result(),
+    };
+
+    let samples = extract_samples(&problem);
+    let header = if samples.is_empty() {
+        format!(
+            "# Samples: {}\n\nNo sample tests found in the statement.\n",
+            problem.title
+        )
+    } else {
+        format!("# Samples: {} ({})\n", problem.title, samples.len())
+    };
+    let mut content = vec![Content::text(header)];
+    content.extend(
+        samples
+            .iter()
+            .enumerate()
+            .map(|(i, s)| Content::text(format_sample(i + 1, s))),
+    );
+    content.push(problem_link(&problem));
+
+    let output = SamplesOutput {
+        source: problem.source,
+        id: problem.id,
+        title: problem.title,
+        samples,
+    };
+    structured_success(&output, content)
+}
@@ -0,0 +1,418 @@
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+use rmcp::model::{CallToolResult, Content, ErrorData};
+use rmcp::service::RequestContext;
+use rmcp::{RoleServer, schemars};
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+
+use crate::client::OjClient;
+use crate::convert::{extract_samples, format_problem};
+use crate::error::domain_error;
+use crate::lang::canonical_language;
+use crate::models::Problem;
+use crate::resources::problem_link;
+
+use super::{problem, structured_success};
+
+pub const DEFAULT_PATTERN: &str = "{source}/{id}-{slug}";
+const DEFAULT_LANGUAGE: &str = "cpp";
+
+/// Built-in solution stubs as `(language, extension, template)`. Templates may use
+/// `{title}`, `{source}`, `{id}` and `{link}`.
+const DEFAULT_TEMPLATES: &[(&str, &str, &str)] = &[
+    (
+        "cpp",
+        "cpp",
+        "// {title}\n// {link}\n#include <bits/stdc++.h>\nusing namespace std;\n\nint main() {\n    ios::sync_with_stdio(false);\n    cin.tie(nullptr);\n\n    return 0;\n}\n",
+    ),
+    (
+        "c",
+        "c",
+        "// {title}\n// {link}\n#include <stdio.h>\n\nint main(void) {\n\n    return 0;\n}\n",
+    ),
+    (
+        "rust",
+        "rs",
+        "// {title}\n// {link}\nuse std::io::{self, Read};\n\nfn main() {\n    let mut input = String::new();\n    io::stdin().read_to_string(&mut input).unwrap();\n    let mut it = input.split_ascii_whitespace();\n}\n",
+    ),
+    (
+        "go",
+        "go",
+        "// {title}\n// {link}\npackage main\n\nimport (\n\t\"bufio\"\n\t\"os\"\n)\n\nfunc main() {\n\tin := bufio.NewReader(os.Stdin)\n\tout := bufio.NewWriter(os.Stdout)\n\tdefer out.Flush()\n\t_ = in\n}\n",
+    ),
+    (
+        "java",
+        "java",
+        "// {title}\n// {link}\nimport java.io.*;\nimport java.util.*;\n\npublic class Main {\n    public static void main(String[] args) throws IOException {\n        BufferedReader in = new BufferedReader(new InputStreamReader(System.in));\n    }\n}\n",
+    ),
+    (
+        "python",
+        "py",
+        "# {title}\n# {link}\nimport sys\n\n\ndef main():\n    data = sys.stdin.read().split()\n\n\nif __name__ == \"__main__\":\n    main()\n",
+    ),
+    (
+        "javascript",
+        "js",
+        "// {title}\n// {link}\nconst input = require(\"fs\").readFileSync(0, \"utf8\").split(/\\s+/);\n",
+    ),
+];
+
+struct Template {
+    extension: String,
+    body: String,
+}
+
+pub struct ScaffoldConfig {
+    root: Option<PathBuf>,
+    /// Whether roots declared by the client are writable too. Only a local (stdio)
+    /// client's roots describe this machine's filesystem.
+    client_roots: bool,
+    pattern: String,
+    templates: HashMap<String, Template>,
+}
+
+impl ScaffoldConfig {
+    /// `template_dir` holds files named after a language (e.g. `cpp.cpp`, `python.py`)
+    /// that replace the built-in stubs; the file's extension is kept for the solution.
+    pub fn new(
+        root: Option<PathBuf>,
+        client_roots: bool,
+        pattern: String,
+        template_dir: Option<&Path>,
+    ) -> Result<Self, String> {
+        let relative = Path::new(&pattern);
+        if pattern.trim().is_empty()
+            || relative
+                .components()
+                .any(|c| !matches!(c, Component::Normal(_)))
+        {
+            return Err(format!(
+                "scaffold pattern '{pattern}' must be a relative path without '.' or '..'"
+            ));
+        }
+
+        let mut templates: HashMap<String, Template> = DEFAULT_TEMPLATES
+            .iter()
+            .map(|(language, extension, body)| {
+                let template = Template {
+                    extension: (*extension).to_owned(),
+                    body: (*body).to_owned(),
+                };
+                ((*language).to_owned(), template)
+            })
+            .collect();
+        if let Some(dir) = template_dir {
+            let entries = std::fs::read_dir(dir)
+                .map_err(|e| format!("cannot read template dir {}: {e}", dir.display()))?;
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let (Some(stem), Some(extension)) = (
+                    path.file_stem().and_then(|s| s.to_str()),
+                    path.extension().and_then(|s| s.to_str()),
+                ) else {
+                    continue;
+                };
+                let body = std::fs::read_to_string(&path)
+                    .map_err(|e| format!("cannot read template {}: {e}", path.display()))?;
+                let template = Template {
+                    extension: extension.to_owned(),
+                    body,
+                };
+                templates.insert(
+                    canonical_language(&stem.to_lowercase()).to_owned(),
+                    template,
+                );
+            }
+        }
+
+        Ok(Self {
+            root,
+            client_roots,
+            pattern,
+            templates,
+        })
+    }
+}
+
+#[derive(Deserialize, schemars::JsonSchema)]
+pub struct ScaffoldParams {
+    #[schemars(description = "Problem source: leetcode, codeforces, atcoder, or luogu")]
+    pub source: String,
+
+    #[schemars(
+        description = "Problem ID on the platform. Examples: '1' or 'two-sum' (leetcode), '1A' (codeforces), 'abc001_1' (atcoder), 'P1001' (luogu)"
+    )]
+    pub id: String,
+
+    #[serde(default)]
+    #[schemars(
+        description = "Language of the solution stub: cpp (default), c, rust, go, java, python, javascript, or one from the server's template dir"
+    )]
+    pub language: Option<String>,
+
+    #[serde(default)]
+    #[schemars(
+        description = "Absolute directory to scaffold under; must be inside the server's workspace root or one of the client's roots (default: the first of those)"
+    )]
+    pub directory: Option<String>,
+
+    #[serde(default)]
+    #[schemars(description = "Overwrite files that already exist (default: false, keep them)")]
+    pub overwrite: Option<bool>,
+}
+
+#[derive(Serialize, schemars::JsonSchema)]
+pub struct ScaffoldOutput {
+    pub directory: String,
+    pub language: String,
+    /// Files written, relative to `directory`.
+    pub created: Vec<String>,
+    /// Files left untouched because they already existed.
+    pub skipped: Vec<String>,
+}
+
+/// Keeps `[A-Za-z0-9._-]`, mapping everything else to `-`, so a value is always a single
+/// safe path component.
+fn sanitize(value: &str) -> String {
+    let cleaned: String = value
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let cleaned = cleaned.trim_start_matches(['.', '-']).trim_end_matches('-');
+    if cleaned.is_empty() {
+        "_".into()
+    } else {
+        cleaned.to_owned()
+    }
+}
+
+fn slugify(title: &str) -> String {
+    let mut slug = String::new();
+    for c in title.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_matches('-').to_owned()
+}
+
+fn fill(template: &str, p: &Problem) -> String {
+    template
+        .replace("{title}", &p.title)
+        .replace("{source}", &p.source)
+        .replace("{id}", &p.id)
+        .replace("{link}", p.link.as_deref().unwrap_or(""))
+}
+
+/// Directories the tool may write under: the configured root plus, when trusted, any
+/// roots the client declared, canonicalized.
+async fn allowed_roots(
+    config: &ScaffoldConfig,
+    context: &RequestContext<RoleServer>,
+) -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = config.root.iter().cloned().collect();
+    let declares_roots = config.client_roots
+        && context
+            .peer
+            .peer_info()
+            .is_some_and(|info| info.capabilities.roots.is_some());
+    if declares_roots {
+        match context.peer.list_roots().await {
+            Ok(result) => roots.extend(
+                result
+                    .roots
+                    .iter()
+                    .filter_map(|r| url::Url::parse(&r.uri).ok()?.to_file_path().ok()),
+            ),
+            Err(e) => tracing::debug!("roots/list failed: {e}"),
+        }
+    }
+    roots
+        .into_iter()
+        .filter_map(|r| std::fs::canonicalize(r).ok())
+        .collect()
+}
+
+fn ensure_within(real: &Path, base: &Path, dir: &Path) -> std::io::Result<()> {
+    if real.starts_with(base) {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(format!(
+            "{} resolves outside {}",
+            dir.display(),
+            base.display()
+        )))
+    }
+}
+
+/// Writes `contents` to `path`, refusing to replace an existing file unless `overwrite`.
+/// The parent directory must resolve within `base` and `path` itself must not be a
+/// symlink, so nothing is written outside the root. Returns whether the file was written.
+async fn write_file(
+    base: &Path,
+    path: &Path,
+    contents: &str,
+    overwrite: bool,
+) -> std::io::Result<bool> {
+    let parent = path.parent().unwrap_or(base);
+    ensure_within(&tokio::fs::canonicalize(parent).await?, base, parent)?;
+    match tokio::fs::symlink_metadata(path).await {
+        Ok(meta) if meta.file_type().is_symlink() => {
+            return Err(std::io::Error::other(format!(
+                "{} is a symbolic link",
+                path.display()
+            )));
+        }
+        Ok(_) if !overwrite => return Ok(false),
+        _ => {}
+    }
+    let mut options = tokio::fs::OpenOptions::new();
+    options.write(true);
+    if overwrite {
+        options.create(true).truncate(true);
+    } else {
+        options.create_new(true);
+    }
+    match options.open(path).await {
+        Ok(mut file) => {
+            file.write_all(contents.as_bytes()).await?;
+            Ok(true)
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn run(
+    client: &OjClient,
+    config: &ScaffoldConfig,
+    params: ScaffoldParams,
+    context: RequestContext<RoleServer>,
+) -> Result<CallToolResult, ErrorData> {
+    let requested = params
+        .language
+        .as_deref()
+        .map(|l| l.trim().to_lowercase())
+        .filter(|l| !l.is_empty())
+        .unwrap_or_else(|| DEFAULT_LANGUAGE.into());
+    let language = canonical_language(&requested).to_owned();
+    let Some(template) = config.templates.get(&language) else {
+        let mut known: Vec<&str> = config.templates.keys().map(String::as_str).collect();
+        known.sort_unstable();
+        return Ok(domain_error(format!(
+            "no solution template for '{requested}', available: {}",
+            known.join(", ")
+        )));
+    };
+
+    let roots = allowed_roots(config, &context).await;
+    let base = match params.directory.as_deref().map(str::trim) {
+        Some(dir) => match std::fs::canonicalize(dir) {
+            Ok(dir) if roots.iter().any(|r| dir.starts_with(r)) => dir,
+            Ok(_) => {
+                return Ok(domain_error(format!(
+                    "'{dir}' is outside the workspace root and the client's roots"
+                )));
+            }
+            Err(e) => return Ok(domain_error(format!("cannot use directory '{dir}': {e}"))),
+        },
+        None => match roots.first() {
+            Some(root) => root.clone(),
+            None => {
+                return Ok(domain_error(
+                    "no workspace to write to: start the server with --workspace-root or use a client that declares roots",
+                ));
+            }
+        },
+    };
+
+    let problem = match problem::fetch(client, &params.source, &params.id).await {
+        Ok(p) => p,
+        Err(e) => return e.into_tool_result(),
+    };
+
+    // Titles without ASCII letters or digits (e.g. Chinese) fall back to the ID.
+    let slug = Some(slugify(&problem.title))
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| problem.id.clone());
+    let relative = config
+        .pattern
+        .replace("{source}", &sanitize(&problem.source))
+        .replace("{id}", &sanitize(&problem.id))
+        .replace("{slug}", &sanitize(&slug));
+    let dir = base.join(relative);
+    let overwrite = params.overwrite.unwrap_or(false);
+
+    let mut files = vec![("README.md".to_owned(), format_problem(&problem))];
+    for (i, sample) in extract_samples(&problem).iter().enumerate() {
+        let n = i + 1;
+        files.push((format!("samples/{n}.in"), format!("{}\n", sample.input)));
+        files.push((format!("samples/{n}.out"), format!("{}\n", sample.output)));
+    }
+    let solution = if language == "java" {
+        "Main.java".to_owned()
+    } else {
+        format!("solution.{}", template.extension)
+    };
+    files.push((solution, fill(&template.body, &problem)));
+
+    let written = async {
+        // Resolve the deepest existing ancestor before creating anything, so a symlink
+        // inside the root cannot lead outside it, and check again once created.
+        let mut existing = dir.as_path();
+        while !existing.exists() {
+            existing = existing.parent().unwrap_or(&base);
+        }
+        ensure_within(&tokio::fs::canonicalize(existing).await?, &base, &dir)?;
+        tokio::fs::create_dir_all(dir.join("samples")).await?;
+        let real = tokio::fs::canonicalize(&dir).await?;
+        ensure_within(&real, &base, &dir)?;
+        let (mut created, mut skipped) = (Vec::new(), Vec::new());
+        for (name, contents) in &files {
+            if write_file(&base, &real.join(name), contents, overwrite).await? {
+                created.push(name.clone());
+            } else {
+                skipped.push(name.clone());
+            }
+        }
+        Ok::<_, std::io::Error>((real, created, skipped))
+    }
+    .await;
+    let (dir, created, skipped) = match written {
+        Ok(w) => w,
+        Err(e) => return Ok(domain_error(format!("scaffolding failed: {e}"))),
+    };
+
+    let mut md = format!(
+        "# Scaffolded {}\n\n- Directory: {}\n- Language: {language}\n",
+        problem.title,
+        dir.display()
+    );
+    if !created.is_empty() {
+        md.push_str(&format!("- Created: {}\n", created.join(", ")));
+    }
+    if !skipped.is_empty() {
+        md.push_str(&format!(
+            "- Kept existing: {} (pass overwrite to replace)\n",
+            skipped.join(", ")
+        ));
+    }
+
+    let output = ScaffoldOutput {
+        directory: dir.display().to_string(),
+        language,
+        created,
+        skipped,
+    };
+    structured_success(&output, vec![Content::text(md), problem_link(&problem)])
+}
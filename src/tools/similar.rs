@@ -4,9 +4,11 @@ use serde::Deserialize;
 
 use crate::client::OjClient;
 use crate::convert::{format_similar, truncate_output};
-use crate::error::{domain_error, format_api_error, protocol_error};
+use crate::error::FetchError;
 use crate::models::SimilarResponse;
 
+use super::structured_success;
+
 #[derive(Deserialize, schemars::JsonSchema)]
 pub struct SimilarParams {
     #[serde(default)]
@@ -42,14 +44,19 @@ pub struct SimilarParams {
     pub source_filter: Option<String>,
 }
 
-pub async fn run(client: &OjClient, params: SimilarParams) -> Result<CallToolResult, ErrorData> {
+pub async fn fetch(
+    client: &OjClient,
+    params: &SimilarParams,
+) -> Result<SimilarResponse, FetchError> {
     let limit = params.limit.unwrap_or(10);
     if !(1..=50).contains(&limit) {
-        return Ok(domain_error("limit must be between 1 and 50"));
+        return Err(FetchError::Domain("limit must be between 1 and 50".into()));
     }
     let threshold = params.threshold.unwrap_or(0.0);
     if !(0.0..=1.0).contains(&threshold) {
-        return Ok(domain_error("threshold must be between 0.0 and 1.0"));
+        return Err(FetchError::Domain(
+            "threshold must be between 0.0 and 1.0".into(),
+        ));
     }
 
     let mut qs = format!("limit={limit}&threshold={threshold}");
@@ -62,7 +69,9 @@ pub async fn run(client: &OjClient, params: SimilarParams) -> Result<CallToolRes
     let path = if !trimmed_query.is_empty() {
         let len = trimmed_query.chars().count();
         if !(3..=2000).contains(&len) {
-            return Ok(domain_error("query must be between 3 and 2000 characters"));
+            return Err(FetchError::Domain(
+                "query must be between 3 and 2000 characters".into(),
+            ));
         }
         format!(
             "/api/v1/similar?q={}&{qs}",
@@ -72,8 +81,8 @@ pub async fn run(client: &OjClient, params: SimilarParams) -> Result<CallToolRes
         let source = params.source.as_deref().map(str::trim).unwrap_or("");
         let id = params.id.as_deref().map(str::trim).unwrap_or("");
         if source.is_empty() || id.is_empty() {
-            return Ok(domain_error(
-                "either 'query' or both 'source' and 'id' must be provided",
+            return Err(FetchError::Domain(
+                "either 'query' or both 'source' and 'id' must be provided".into(),
             ));
         }
         format!(
@@ -83,20 +92,15 @@ pub async fn run(client: &OjClient, params: SimilarParams) -> Result<CallToolRes
         )
     };
 
-    let resp = client.get_raw(&path).await?;
-
-    if resp.status != 200 {
-        return Ok(domain_error(format_api_error(resp.status, &resp.body)));
-    }
-    if !resp.is_json {
-        return Err(protocol_error("unexpected non-JSON response"));
-    }
+    client.get_json(&path).await
+}
 
-    let parsed: SimilarResponse = serde_json::from_str(&resp.body)
-        .map_err(|e| protocol_error(format!("invalid JSON: {e}")))?;
+pub async fn run(client: &OjClient, params: SimilarParams) -> Result<CallToolResult, ErrorData> {
+    let parsed = match fetch(client, &params).await {
+        Ok(r) => r,
+        Err(e) => return e.into_tool_result(),
+    };
 
     let md = format_similar(&parsed);
-    Ok(CallToolResult::success(vec![Content::text(
-        truncate_output(md),
-    )]))
+    structured_success(&parsed, vec![Content::text(truncate_output(md))])
 }
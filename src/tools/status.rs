@@ -2,24 +2,25 @@ use rmcp::model::{CallToolResult, Content, ErrorData};
 
 use crate::client::OjClient;
 use crate::convert::{format_status, truncate_output};
-use crate::error::{domain_error, format_api_error, protocol_error};
+use crate::error::FetchError;
 use crate::models::StatusResponse;
 
-pub async fn run(client: &OjClient) -> Result<CallToolResult, ErrorData> {
-    let resp = client.get_raw("/status").await?;
+use super::structured_success;
 
-    if resp.status != 200 {
-        return Ok(domain_error(format_api_error(resp.status, &resp.body)));
-    }
-    if !resp.is_json {
-        return Err(protocol_error("unexpected non-JSON response"));
-    }
+pub async fn fetch(client: &OjClient) -> Result<StatusResponse, FetchError> {
+    let parsed: StatusResponse = client.get_json("/status").await?;
+    client
+        .recent()
+        .record_sources(parsed.platforms.iter().map(|p| p.source.as_str()));
+    Ok(parsed)
+}
 
-    let parsed: StatusResponse = serde_json::from_str(&resp.body)
-        .map_err(|e| protocol_error(format!("invalid JSON: {e}")))?;
+pub async fn run(client: &OjClient) -> Result<CallToolResult, ErrorData> {
+    let parsed = match fetch(client).await {
+        Ok(s) => s,
+        Err(e) => return e.into_tool_result(),
+    };
 
     let md = format_status(&parsed);
-    Ok(CallToolResult::success(vec![Content::text(
-        truncate_output(md),
-    )]))
+    structured_success(&parsed, vec![Content::text(truncate_output(md))])
 }